@@ -1,5 +1,16 @@
 use crate::board::Board;
+use crate::chess960::random_index;
+use crate::engine::{ComputerPlayer, SearchLimits, SearchResult};
+use crate::pgn::parse_pgn;
+use crate::pieces::PieceColor;
+use crate::uci::{UciEngine, UciError};
 use std::error;
+use std::fs;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Time the search of a hint is given.
+const HINT_TIME: Duration = Duration::from_millis(500);
 
 /// Application result type.
 pub type AppResult<T> = std::result::Result<T, Box<dyn error::Error>>;
@@ -14,6 +25,22 @@ pub struct App {
 
     /// show help popup
     pub show_popup: bool,
+    /// message shown under the board
+    pub message: Option<String>,
+    /// computer opponent, none for two human players
+    pub computer: Option<ComputerPlayer>,
+    /// external engine the computer plays with, its own search otherwise
+    pub uci_engine: Option<Arc<Mutex<UciEngine>>>,
+    /// name the external engine gave, read once when it started
+    pub uci_engine_name: Option<String>,
+    /// error shown in a popup until a key is pressed
+    pub error: Option<String>,
+    /// show the evaluation bar next to the board
+    pub show_evaluation: bool,
+    /// search of the hint asked for, if any
+    pub hint_search: Option<ComputerPlayer>,
+    /// hint found with the key of the position it was searched from
+    pub hint: Option<(u64, SearchResult)>,
 }
 
 impl Default for App {
@@ -22,6 +49,14 @@ impl Default for App {
             running: true,
             board: Board::default(),
             show_popup: false,
+            message: None,
+            computer: None,
+            uci_engine: None,
+            uci_engine_name: None,
+            error: None,
+            show_evaluation: false,
+            hint_search: None,
+            hint: None,
         }
     }
 }
@@ -36,8 +71,163 @@ impl App {
         self.show_popup = !self.show_popup;
     }
 
-    /// Handles the tick event of the terminal.
-    pub fn tick(&self) {}
+    /// Shows or hides the evaluation bar, hidden by default not to spoil the game.
+    pub fn toggle_evaluation(&mut self) {
+        self.show_evaluation = !self.show_evaluation;
+    }
+
+    /// Handles the tick event of the terminal, the hint is shown and the computer plays its move
+    /// once found.
+    pub fn tick(&mut self) {
+        if let Some(hint_search) = self.hint_search.as_mut() {
+            if let Some(Ok(result)) = hint_search.poll(&self.board) {
+                self.hint = Some((self.board.zobrist_key, result));
+            }
+            // The search is over, whether its result could be used or not
+            if !hint_search.is_thinking() {
+                self.hint_search = None;
+                self.message = None;
+            }
+        }
+
+        if !self.is_computer_turn() {
+            return;
+        }
+        let Some(computer) = self.computer.as_mut() else {
+            return;
+        };
+        if !computer.is_thinking() {
+            computer.think(&self.board);
+        } else if let Some(result) = computer.poll(&self.board) {
+            let played = result.and_then(|result| match result.best_move {
+                Some(best_move) => self
+                    .board
+                    .try_move(
+                        best_move.from.coordinates(),
+                        best_move.to.coordinates(),
+                        best_move.promotion,
+                    )
+                    .map(|_| ())
+                    .map_err(|error| UciError::IllegalMove(format!("{}: {}", best_move, error))),
+                None => Ok(()),
+            });
+            // The engine can't be relied on anymore, the game goes on between two players
+            if let Err(error) = played {
+                self.error = Some(error.to_string());
+                self.computer = None;
+                self.uci_engine = None;
+                self.uci_engine_name = None;
+            }
+        }
+    }
+
+    /// Hint of the current position, it is only shown in the position it was searched from.
+    pub fn current_hint(&self) -> Option<&SearchResult> {
+        self.hint
+            .as_ref()
+            .filter(|(key, _)| *key == self.board.zobrist_key)
+            .map(|(_, result)| result)
+    }
+
+    /// Is the computer the one to play?
+    pub fn is_computer_turn(&self) -> bool {
+        self.computer
+            .as_ref()
+            .is_some_and(|computer| computer.color == self.board.player_turn)
+            && !self.board.is_promotion
+            && !self.board.is_game_over()
+    }
+
+    /// Sets the color the computer plays, none for two human players.
+    pub fn set_computer(&mut self, color: Option<PieceColor>) {
+        let limits = SearchLimits::default();
+        self.computer = color.map(|color| match &self.uci_engine {
+            Some(uci_engine) => ComputerPlayer::with_uci_engine(color, limits, uci_engine.clone()),
+            None => ComputerPlayer::new(color, limits),
+        });
+        let player = self
+            .uci_engine_name
+            .clone()
+            .unwrap_or_else(|| "The computer".to_string());
+        self.message = Some(match color {
+            Some(PieceColor::White) => format!("{} plays White", player),
+            Some(PieceColor::Black) => format!("{} plays Black", player),
+            None => "Two players game".to_string(),
+        });
+    }
+
+    /// Starts the UCI engine at the given path, it plays Black until another color is chosen.
+    pub fn start_uci_engine(&mut self, path: &str) -> AppResult<()> {
+        let uci_engine = UciEngine::start(path)?;
+        // The UI never waits on the engine, it may be busy searching
+        self.uci_engine_name = Some(uci_engine.name.clone());
+        self.uci_engine = Some(Arc::new(Mutex::new(uci_engine)));
+        self.set_computer(Some(PieceColor::Black));
+        Ok(())
+    }
+
+    /// Switches the computer between Black, White and no computer, before the first move or once
+    /// the game is over.
+    pub fn switch_computer(&mut self) {
+        if self.board.is_game_over() || self.board.moves_history.is_empty() {
+            let color = match self.computer.as_ref().map(|computer| computer.color) {
+                None => Some(PieceColor::Black),
+                Some(PieceColor::Black) => Some(PieceColor::White),
+                Some(PieceColor::White) => None,
+            };
+            self.set_computer(color);
+        }
+    }
+
+    /// Looks for the best move of the player to play, its cells are highlighted once found.
+    pub fn ask_hint(&mut self) {
+        if self.is_computer_turn() || self.board.is_promotion || self.board.is_game_over() {
+            return;
+        }
+        let limits = SearchLimits {
+            time: HINT_TIME,
+            ..SearchLimits::default()
+        };
+        let mut hint_search = ComputerPlayer::new(self.board.player_turn, limits);
+        hint_search.think(&self.board);
+        self.hint_search = Some(hint_search);
+        self.message = Some("Looking for the best move...".to_string());
+    }
+
+    /// Plays the selected cell, the board can't be played on while the computer thinks.
+    pub fn select_cell(&mut self) {
+        if !self.is_computer_turn() {
+            self.board.select_cell();
+        }
+    }
+
+    /// Stops the searches of the computer and of the hint, the position they started from is gone.
+    fn cancel_searches(&mut self) {
+        if let Some(computer) = self.computer.as_mut() {
+            computer.cancel();
+        }
+        if self.hint_search.take().is_some() {
+            self.message = None;
+        }
+    }
+
+    /// Takes back the latest move, and the computer's answer before it against the computer.
+    pub fn undo(&mut self) {
+        self.cancel_searches();
+        self.board.undo();
+        if self.is_computer_turn() && !self.board.moves_history.is_empty() {
+            self.board.undo();
+        }
+    }
+
+    /// Plays again the latest move taken back, and the computer's answer after it.
+    pub fn redo(&mut self) {
+        self.cancel_searches();
+        self.board.redo();
+        if self.is_computer_turn() && !self.board.undone_moves.is_empty() {
+            self.board.redo();
+        }
+    }
 
     /// Set running to false to quit the application.
     pub fn quit(&mut self) {
@@ -45,8 +235,121 @@ impl App {
     }
 
     pub fn restart(&mut self) {
-        if self.board.is_pat || self.board.is_checkmate {
-            self.board = Board::default()
+        if self.board.is_game_over() {
+            self.cancel_searches();
+            self.board = if self.board.is_chess960() {
+                Board::chess960(random_index()).unwrap_or_default()
+            } else {
+                Board::standard()
+            }
+        }
+    }
+
+    /// Starts a Chess960 game from a random position once the game is over or before its first move.
+    pub fn restart_chess960(&mut self) {
+        if self.board.is_game_over() || self.board.moves_history.is_empty() {
+            // A random number is always a valid position
+            let _ = self.start_chess960(None);
         }
     }
-}
\ No newline at end of file
+
+    /// Starts a Chess960 game from the given position number, a random one if none is given.
+    pub fn start_chess960(&mut self, index: Option<u16>) -> AppResult<()> {
+        let index = index.unwrap_or_else(random_index);
+        match Board::chess960(index) {
+            Some(board) => {
+                self.cancel_searches();
+                self.board = board;
+                self.message = Some(format!("Chess960 position {}", index));
+                Ok(())
+            }
+            None => Err(format!(
+                "{} is not a Chess960 position, they go from 0 to 959",
+                index
+            )
+            .into()),
+        }
+    }
+
+    /// Loads the first game of a PGN file, replayed up to its last move.
+    pub fn load_pgn(&mut self, path: &str) -> AppResult<()> {
+        let content = fs::read_to_string(path)?;
+        match parse_pgn(&content)?.into_iter().next() {
+            Some(game) => {
+                self.cancel_searches();
+                self.board = game.board;
+                self.message = Some(format!("Game loaded from {}", path));
+                Ok(())
+            }
+            None => Err(format!("{} does not contain any game", path).into()),
+        }
+    }
+
+    /// Saves the current game as a PGN file in the working directory.
+    pub fn save_pgn(&mut self) {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+        let file_name = format!("chess_{}.pgn", timestamp);
+
+        self.message = Some(match fs::write(&file_name, self.board.to_pgn()) {
+            Ok(()) => format!("Game saved to {}", file_name),
+            Err(error) => format!("Could not save the game: {}", error),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::{
+        app::App,
+        engine::{search, SearchLimits},
+        san::apply_move,
+    };
+
+    #[test]
+    fn hint_of_the_current_position() {
+        let mut app = App::new();
+        assert!(app.current_hint().is_none());
+
+        let result = search(
+            &app.board,
+            SearchLimits {
+                max_depth: 1,
+                ..SearchLimits::default()
+            },
+        );
+        app.hint = Some((app.board.zobrist_key, result.clone()));
+        assert_eq!(app.current_hint(), Some(&result));
+
+        // The hint is gone once a move is played
+        apply_move(&mut app.board, [6, 3], [4, 3], None);
+        assert!(app.current_hint().is_none());
+    }
+
+    #[test]
+    fn hint_message_goes_away() {
+        // The search is cancelled
+        let mut app = App::new();
+        app.ask_hint();
+        assert!(app.message.is_some());
+        app.undo();
+        assert!(app.hint_search.is_none());
+        assert!(app.message.is_none());
+
+        // The position changed before the hint was found
+        app.ask_hint();
+        apply_move(&mut app.board, [6, 3], [4, 3], None);
+        let started = Instant::now();
+        while app.hint_search.is_some() {
+            assert!(started.elapsed() < Duration::from_secs(30));
+            app.tick();
+            std::thread::sleep(Duration::from_millis(5));
+        }
+        assert!(app.message.is_none());
+        assert!(app.current_hint().is_none());
+    }
+}
@@ -0,0 +1,619 @@
+use crate::{
+    moves::{home_line, CastlingRights, CastlingSide, Move, MoveKind, Square, PROMOTION_PIECES},
+    pieces::{PieceColor, PieceType},
+};
+
+// Set of cells, the bit y * 8 + x stands for the cell [y, x] so a8 is the lowest bit
+pub type Bitboard = u64;
+
+pub const FILE_A: Bitboard = 0x0101_0101_0101_0101;
+const FILE_H: Bitboard = FILE_A << 7;
+
+// Directions as [dy, dx], the rook ones first then the bishop ones, each next to its opposite
+const DIRECTIONS: [[i8; 2]; 8] = [
+    [-1, 0],
+    [1, 0],
+    [0, -1],
+    [0, 1],
+    [-1, -1],
+    [1, 1],
+    [-1, 1],
+    [1, -1],
+];
+const KNIGHT_JUMPS: [[i8; 2]; 8] = [
+    [-2, -1],
+    [-2, 1],
+    [-1, -2],
+    [-1, 2],
+    [1, -2],
+    [1, 2],
+    [2, -1],
+    [2, 1],
+];
+
+const fn is_on_board(y: i8, x: i8) -> bool {
+    y >= 0 && y < 8 && x >= 0 && x < 8
+}
+
+const fn bit(y: i8, x: i8) -> Bitboard {
+    1 << (y * 8 + x)
+}
+
+const fn jump_table(jumps: &[[i8; 2]]) -> [Bitboard; 64] {
+    let mut table = [0; 64];
+    let mut square = 0;
+    while square < 64 {
+        let (y, x) = ((square / 8) as i8, (square % 8) as i8);
+        let mut i = 0;
+        while i < jumps.len() {
+            let (new_y, new_x) = (y + jumps[i][0], x + jumps[i][1]);
+            if is_on_board(new_y, new_x) {
+                table[square] |= bit(new_y, new_x);
+            }
+            i += 1;
+        }
+        square += 1;
+    }
+    table
+}
+
+// Cells a pawn of each color takes on, white pawns go up the board
+const fn pawn_attacks_table() -> [[Bitboard; 64]; 2] {
+    [
+        jump_table(&[[-1, -1], [-1, 1]]),
+        jump_table(&[[1, -1], [1, 1]]),
+    ]
+}
+
+// Cells from a cell to the edge of the board in each direction, the cell itself excluded
+const fn rays_table() -> [[Bitboard; 64]; 8] {
+    let mut table = [[0; 64]; 8];
+    let mut direction = 0;
+    while direction < 8 {
+        let mut square = 0;
+        while square < 64 {
+            let [dy, dx] = DIRECTIONS[direction];
+            let (mut y, mut x) = ((square / 8) as i8 + dy, (square % 8) as i8 + dx);
+            while is_on_board(y, x) {
+                table[direction][square] |= bit(y, x);
+                y += dy;
+                x += dx;
+            }
+            square += 1;
+        }
+        direction += 1;
+    }
+    table
+}
+
+// Cells strictly between two cells on the same row, column or diagonal, and the whole line
+// going through both of them
+const fn lines_tables() -> ([[Bitboard; 64]; 64], [[Bitboard; 64]; 64]) {
+    let rays = rays_table();
+    let mut between = [[0; 64]; 64];
+    let mut line = [[0; 64]; 64];
+    let mut from = 0;
+    while from < 64 {
+        let mut direction = 0;
+        while direction < 8 {
+            let [dy, dx] = DIRECTIONS[direction];
+            // The opposite direction is next to this one in DIRECTIONS
+            let opposite = direction ^ 1;
+            let whole_line = rays[direction][from] | rays[opposite][from] | (1 << from);
+            let (mut y, mut x) = ((from / 8) as i8 + dy, (from % 8) as i8 + dx);
+            let mut passed: Bitboard = 0;
+            while is_on_board(y, x) {
+                let to = (y * 8 + x) as usize;
+                between[from][to] = passed;
+                line[from][to] = whole_line;
+                passed |= 1 << to;
+                y += dy;
+                x += dx;
+            }
+            direction += 1;
+        }
+        from += 1;
+    }
+    (between, line)
+}
+
+static KNIGHT_ATTACKS: [Bitboard; 64] = jump_table(&KNIGHT_JUMPS);
+static KING_ATTACKS: [Bitboard; 64] = jump_table(&DIRECTIONS);
+static PAWN_ATTACKS: [[Bitboard; 64]; 2] = pawn_attacks_table();
+static RAYS: [[Bitboard; 64]; 8] = rays_table();
+static LINES: ([[Bitboard; 64]; 64], [[Bitboard; 64]; 64]) = lines_tables();
+
+fn between(from: usize, to: usize) -> Bitboard {
+    LINES.0[from][to]
+}
+
+fn line(from: usize, to: usize) -> Bitboard {
+    LINES.1[from][to]
+}
+
+// Cells a slider sees in the given directions, the first piece met in each direction included
+fn sliding_attacks(square: usize, occupied: Bitboard, directions: &[usize]) -> Bitboard {
+    let mut attacks = 0;
+    for &direction in directions {
+        let ray = RAYS[direction][square];
+        let blockers = ray & occupied;
+        attacks |= if blockers == 0 {
+            ray
+        } else {
+            // Going down the board or to the right the first blocker has the lowest bit
+            let [dy, dx] = DIRECTIONS[direction];
+            let blocker = if dy * 8 + dx > 0 {
+                blockers.trailing_zeros()
+            } else {
+                63 - blockers.leading_zeros()
+            };
+            ray ^ RAYS[direction][blocker as usize]
+        };
+    }
+    attacks
+}
+
+pub fn rook_attacks(square: usize, occupied: Bitboard) -> Bitboard {
+    sliding_attacks(square, occupied, &[0, 1, 2, 3])
+}
+
+pub fn bishop_attacks(square: usize, occupied: Bitboard) -> Bitboard {
+    sliding_attacks(square, occupied, &[4, 5, 6, 7])
+}
+
+pub fn knight_attacks(square: usize) -> Bitboard {
+    KNIGHT_ATTACKS[square]
+}
+
+pub fn king_attacks(square: usize) -> Bitboard {
+    KING_ATTACKS[square]
+}
+
+// Every cell the given pawns attack
+pub fn pawn_attacks(pawns: Bitboard, color: PieceColor) -> Bitboard {
+    match color {
+        PieceColor::White => ((pawns & !FILE_A) >> 9) | ((pawns & !FILE_H) >> 7),
+        PieceColor::Black => ((pawns & !FILE_A) << 7) | ((pawns & !FILE_H) << 9),
+    }
+}
+
+pub fn square_index(coordinates: [i8; 2]) -> usize {
+    (coordinates[0] * 8 + coordinates[1]) as usize
+}
+
+pub fn square_coordinates(square: usize) -> [i8; 2] {
+    [(square / 8) as i8, (square % 8) as i8]
+}
+
+// Cells of a set from the lowest bit to the highest one
+pub fn squares(mut bitboard: Bitboard) -> impl Iterator<Item = usize> {
+    std::iter::from_fn(move || {
+        if bitboard == 0 {
+            return None;
+        }
+        let square = bitboard.trailing_zeros() as usize;
+        bitboard &= bitboard - 1;
+        Some(square)
+    })
+}
+
+pub(crate) fn color_index(color: PieceColor) -> usize {
+    match color {
+        PieceColor::White => 0,
+        PieceColor::Black => 1,
+    }
+}
+
+const PIECE_TYPES: [PieceType; 6] = [
+    PieceType::Pawn,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Queen,
+    PieceType::King,
+    PieceType::Knight,
+];
+
+pub(crate) fn piece_index(piece_type: PieceType) -> usize {
+    match piece_type {
+        PieceType::Pawn => 0,
+        PieceType::Rook => 1,
+        PieceType::Bishop => 2,
+        PieceType::Queen => 3,
+        PieceType::King => 4,
+        PieceType::Knight => 5,
+    }
+}
+
+fn opposite(color: PieceColor) -> PieceColor {
+    match color {
+        PieceColor::White => PieceColor::Black,
+        PieceColor::Black => PieceColor::White,
+    }
+}
+
+// The pieces of a position as one set per color and piece type
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Bitboards {
+    pub pieces: [[Bitboard; 6]; 2],
+    pub colors: [Bitboard; 2],
+}
+
+impl Bitboards {
+    pub fn from_board(board: &[[Option<(PieceType, PieceColor)>; 8]; 8]) -> Self {
+        let mut bitboards = Self::default();
+        for (y, row) in board.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if let Some((piece_type, piece_color)) = cell {
+                    bitboards.add(*piece_type, *piece_color, y * 8 + x);
+                }
+            }
+        }
+        bitboards
+    }
+
+    fn add(&mut self, piece_type: PieceType, color: PieceColor, square: usize) {
+        self.pieces[color_index(color)][piece_index(piece_type)] |= 1 << square;
+        self.colors[color_index(color)] |= 1 << square;
+    }
+
+    fn remove(&mut self, piece_type: PieceType, color: PieceColor, square: usize) {
+        self.pieces[color_index(color)][piece_index(piece_type)] &= !(1 << square);
+        self.colors[color_index(color)] &= !(1 << square);
+    }
+
+    pub fn occupied(&self) -> Bitboard {
+        self.colors[0] | self.colors[1]
+    }
+
+    pub fn get(&self, piece_type: PieceType, color: PieceColor) -> Bitboard {
+        self.pieces[color_index(color)][piece_index(piece_type)]
+    }
+
+    pub fn piece_type_at(&self, square: usize, color: PieceColor) -> Option<PieceType> {
+        PIECE_TYPES
+            .into_iter()
+            .find(|piece_type| self.get(*piece_type, color) & (1 << square) != 0)
+    }
+
+    pub fn king_square(&self, color: PieceColor) -> Option<usize> {
+        squares(self.get(PieceType::King, color)).next()
+    }
+
+    // Pieces of the given color attacking the cell when the board holds the occupied cells
+    pub fn attackers(&self, square: usize, by: PieceColor, occupied: Bitboard) -> Bitboard {
+        let queens = self.get(PieceType::Queen, by);
+        (KNIGHT_ATTACKS[square] & self.get(PieceType::Knight, by))
+            | (KING_ATTACKS[square] & self.get(PieceType::King, by))
+            | (PAWN_ATTACKS[color_index(opposite(by))][square] & self.get(PieceType::Pawn, by))
+            | (rook_attacks(square, occupied) & (self.get(PieceType::Rook, by) | queens))
+            | (bishop_attacks(square, occupied) & (self.get(PieceType::Bishop, by) | queens))
+    }
+
+    pub fn is_attacked(&self, square: usize, by: PieceColor, occupied: Bitboard) -> bool {
+        self.attackers(square, by, occupied) != 0
+    }
+
+    // Every cell the pieces of the given color attack
+    pub fn attacked_cells(&self, by: PieceColor, occupied: Bitboard) -> Bitboard {
+        let mut attacks = pawn_attacks(self.get(PieceType::Pawn, by), by);
+        for square in squares(self.get(PieceType::Knight, by)) {
+            attacks |= KNIGHT_ATTACKS[square];
+        }
+        for square in squares(self.get(PieceType::King, by)) {
+            attacks |= KING_ATTACKS[square];
+        }
+        let queens = self.get(PieceType::Queen, by);
+        for square in squares(self.get(PieceType::Rook, by) | queens) {
+            attacks |= rook_attacks(square, occupied);
+        }
+        for square in squares(self.get(PieceType::Bishop, by) | queens) {
+            attacks |= bishop_attacks(square, occupied);
+        }
+        attacks
+    }
+
+    pub fn is_in_check(&self, color: PieceColor) -> bool {
+        self.king_square(color)
+            .is_some_and(|king| self.is_attacked(king, opposite(color), self.occupied()))
+    }
+
+    // The king goes to the g or c file and the rook to the f or d file: every cell they go
+    // through must be empty but for themselves, and the cells of the king must not be attacked
+    // once both left their cells
+    pub fn can_castle(
+        &self,
+        color: PieceColor,
+        king: usize,
+        rook: usize,
+        side: CastlingSide,
+    ) -> bool {
+        let line = home_line(color) as usize;
+        if king / 8 != line || self.get(PieceType::Rook, color) & (1 << rook) == 0 {
+            return false;
+        }
+        let king_destination = line * 8 + side.king_destination_x() as usize;
+        let rook_destination = line * 8 + side.rook_destination_x() as usize;
+        let king_path = between(king, king_destination) | (1 << king_destination) | (1 << king);
+        let rook_path = between(rook, rook_destination) | (1 << rook_destination);
+        let occupied = self.occupied() & !(1 << king) & !(1 << rook);
+
+        (king_path | rook_path) & occupied == 0
+            && squares(king_path).all(|square| !self.is_attacked(square, opposite(color), occupied))
+    }
+}
+
+// Legal moves of one player, the checks and pins are found once for the whole position
+pub struct MoveGenerator {
+    bitboards: Bitboards,
+    color: PieceColor,
+    en_passant: Option<usize>,
+    castling_rights: CastlingRights,
+    king: Option<usize>,
+    checkers: Bitboard,
+    // Cells a piece other than the king has to go to, to take the checking piece or block it
+    check_mask: Bitboard,
+    pinned: Bitboard,
+    // Cells the enemy attacks, seen through the king so that it can't step back along a line
+    enemy_attacks: Bitboard,
+}
+
+impl MoveGenerator {
+    // The en passant target is the cell a pawn of the other player jumped over on the latest move
+    pub fn new(
+        bitboards: Bitboards,
+        color: PieceColor,
+        en_passant: Option<Square>,
+        castling_rights: CastlingRights,
+    ) -> Self {
+        let enemy = opposite(color);
+        let occupied = bitboards.occupied();
+        let king = bitboards.king_square(color);
+
+        let mut generator = Self {
+            bitboards,
+            color,
+            en_passant: en_passant.map(|square| square_index(square.coordinates())),
+            castling_rights,
+            king,
+            checkers: 0,
+            check_mask: !0,
+            pinned: 0,
+            enemy_attacks: 0,
+        };
+        let Some(king) = king else {
+            return generator;
+        };
+
+        generator.checkers = bitboards.attackers(king, enemy, occupied);
+        generator.check_mask = match generator.checkers.count_ones() {
+            0 => !0,
+            1 => generator.checkers | between(king, generator.checkers.trailing_zeros() as usize),
+            _ => 0,
+        };
+        generator.enemy_attacks = bitboards.attacked_cells(enemy, occupied & !(1 << king));
+
+        // A piece alone between the king and an enemy slider can only move along their line
+        let queens = bitboards.get(PieceType::Queen, enemy);
+        let snipers = (rook_attacks(king, 0) & (bitboards.get(PieceType::Rook, enemy) | queens))
+            | (bishop_attacks(king, 0) & (bitboards.get(PieceType::Bishop, enemy) | queens));
+        for sniper in squares(snipers) {
+            let blockers = between(king, sniper) & occupied;
+            if blockers.count_ones() == 1 {
+                generator.pinned |= blockers & bitboards.colors[color_index(color)];
+            }
+        }
+        generator
+    }
+
+    // Cells the given piece standing on the cell can legally go to, castling is the king going
+    // on its own rook
+    pub fn targets(&self, piece_type: PieceType, from: usize) -> Bitboard {
+        let own = self.bitboards.colors[color_index(self.color)];
+        let enemy = self.bitboards.colors[color_index(opposite(self.color))];
+        let occupied = own | enemy;
+
+        if piece_type == PieceType::King {
+            return self.king_targets(from, own);
+        }
+        if self.checkers.count_ones() > 1 {
+            return 0;
+        }
+
+        let mut targets = match piece_type {
+            PieceType::Knight => KNIGHT_ATTACKS[from] & !own,
+            PieceType::Bishop => bishop_attacks(from, occupied) & !own,
+            PieceType::Rook => rook_attacks(from, occupied) & !own,
+            PieceType::Queen => {
+                (rook_attacks(from, occupied) | bishop_attacks(from, occupied)) & !own
+            }
+            _ => self.pawn_targets(from, occupied, enemy),
+        };
+        targets &= self.check_mask;
+        if let Some(king) = self.king {
+            if self.pinned & (1 << from) != 0 {
+                targets &= line(king, from);
+            }
+        }
+        if piece_type == PieceType::Pawn {
+            targets |= self.en_passant_target(from);
+        }
+        targets
+    }
+
+    fn pawn_targets(&self, from: usize, occupied: Bitboard, enemy: Bitboard) -> Bitboard {
+        let empty = !occupied;
+        let (single, double) = match self.color {
+            PieceColor::White => {
+                let single = (1 << from >> 8) & empty;
+                (
+                    single,
+                    if from / 8 == 6 {
+                        (single >> 8) & empty
+                    } else {
+                        0
+                    },
+                )
+            }
+            PieceColor::Black => {
+                let single = (1 << from << 8) & empty;
+                (
+                    single,
+                    if from / 8 == 1 {
+                        (single << 8) & empty
+                    } else {
+                        0
+                    },
+                )
+            }
+        };
+        single | double | (PAWN_ATTACKS[color_index(self.color)][from] & enemy)
+    }
+
+    // Taking en passant removes two pawns from the same row, the king is checked on the
+    // position after the move
+    fn en_passant_target(&self, from: usize) -> Bitboard {
+        let Some(target) = self.en_passant else {
+            return 0;
+        };
+        if PAWN_ATTACKS[color_index(self.color)][from] & (1 << target) == 0 {
+            return 0;
+        }
+        let taken = match self.color {
+            PieceColor::White => target + 8,
+            PieceColor::Black => target - 8,
+        };
+        let enemy = opposite(self.color);
+        if self.bitboards.get(PieceType::Pawn, enemy) & (1 << taken) == 0 {
+            return 0;
+        }
+
+        let mut after = self.bitboards;
+        after.remove(PieceType::Pawn, enemy, taken);
+        after.remove(PieceType::Pawn, self.color, from);
+        after.add(PieceType::Pawn, self.color, target);
+        match self.king {
+            Some(king) if after.is_attacked(king, enemy, after.occupied()) => 0,
+            _ => 1 << target,
+        }
+    }
+
+    fn king_targets(&self, from: usize, own: Bitboard) -> Bitboard {
+        let mut targets = KING_ATTACKS[from] & !own & !self.enemy_attacks;
+
+        if self.checkers == 0 {
+            let line = home_line(self.color) as usize;
+            for side in [CastlingSide::Queen, CastlingSide::King] {
+                if let Some(rook_x) = self.castling_rights.rook_x(self.color, side) {
+                    let rook = line * 8 + rook_x as usize;
+                    if self.bitboards.can_castle(self.color, from, rook, side) {
+                        targets |= 1 << rook;
+                    }
+                }
+            }
+        }
+        targets
+    }
+
+    // Every legal move, a pawn reaching the last row gives one move for each promotion piece
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let enemy = opposite(self.color);
+        let mut legal_moves: Vec<Move> = Vec::with_capacity(64);
+
+        for piece_type in PIECE_TYPES {
+            for from in squares(self.bitboards.get(piece_type, self.color)) {
+                for to in squares(self.targets(piece_type, from)) {
+                    let mut piece_move = Move {
+                        from: Square::from(square_coordinates(from)),
+                        to: Square::from(square_coordinates(to)),
+                        piece_type,
+                        piece_color: self.color,
+                        captured: self.bitboards.piece_type_at(to, enemy),
+                        promotion: None,
+                        kind: MoveKind::Normal,
+                    };
+                    match piece_type {
+                        PieceType::Pawn if Some(to) == self.en_passant => {
+                            piece_move.kind = MoveKind::EnPassant;
+                            piece_move.captured = Some(PieceType::Pawn);
+                        }
+                        PieceType::Pawn if from.abs_diff(to) == 16 => {
+                            piece_move.kind = MoveKind::DoublePawnPush;
+                        }
+                        PieceType::King
+                            if self.bitboards.colors[color_index(self.color)] & (1 << to) != 0 =>
+                        {
+                            piece_move.kind = MoveKind::Castling(if to > from {
+                                CastlingSide::King
+                            } else {
+                                CastlingSide::Queen
+                            });
+                        }
+                        _ => {}
+                    }
+
+                    if piece_type == PieceType::Pawn && (to / 8 == 0 || to / 8 == 7) {
+                        for promotion in PROMOTION_PIECES {
+                            legal_moves.push(Move {
+                                promotion: Some(promotion),
+                                ..piece_move
+                            });
+                        }
+                    } else {
+                        legal_moves.push(piece_move);
+                    }
+                }
+            }
+        }
+        legal_moves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bitboard::{
+            between, bishop_attacks, line, rook_attacks, square_index, squares, Bitboards,
+            KNIGHT_ATTACKS,
+        },
+        board::Board,
+        pieces::{PieceColor, PieceType},
+    };
+
+    #[test]
+    fn attack_tables() {
+        // A knight in the corner a8 goes to b6 and c7
+        assert_eq!(
+            squares(KNIGHT_ATTACKS[square_index([0, 0])]).collect::<Vec<usize>>(),
+            vec![square_index([1, 2]), square_index([2, 1])]
+        );
+        // A rook on d4 stopped by pieces on d6 and f4
+        let occupied = (1 << square_index([2, 3])) | (1 << square_index([4, 5]));
+        assert_eq!(
+            rook_attacks(square_index([4, 3]), occupied).count_ones(),
+            2 + 3 + 3 + 2
+        );
+        assert_eq!(bishop_attacks(square_index([0, 0]), 0).count_ones(), 7);
+
+        assert_eq!(
+            between(square_index([7, 4]), square_index([7, 7])).count_ones(),
+            2
+        );
+        assert_eq!(between(square_index([0, 0]), square_index([1, 2])), 0);
+        assert_eq!(
+            line(square_index([0, 0]), square_index([3, 3])).count_ones(),
+            8
+        );
+    }
+
+    #[test]
+    fn checks() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K2r w - - 0 1").unwrap();
+        let bitboards = Bitboards::from_board(&board.board);
+        assert!(bitboards.is_in_check(PieceColor::White));
+        assert!(!bitboards.is_in_check(PieceColor::Black));
+        assert_eq!(
+            bitboards.piece_type_at(square_index([7, 7]), PieceColor::Black),
+            Some(PieceType::Rook)
+        );
+    }
+}
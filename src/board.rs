@@ -1,13 +1,24 @@
+use std::{error::Error, fmt};
+
 use crate::{
+    bitboard::{Bitboards, MoveGenerator},
+    chess960,
     constants::{BLACK, UNDEFINED_POSITION, WHITE},
+    moves::{
+        CastlingRights, CastlingSide, IllegalMoveError, Move, MoveKind, MoveOutcome, MoveUndo,
+        Square, PROMOTION_PIECES,
+    },
     pieces::{
         bishop::Bishop, king::King, knight::Knight, pawn::Pawn, queen::Queen, rook::Rook,
         PieceColor, PieceType,
     },
+    san::letter_to_piece,
+    status::GameStatus,
     utils::{
-        convert_position_into_notation, get_int_from_char, get_king_coordinates, get_piece_color,
-        get_piece_type, get_player_turn_in_modulo, is_getting_checked, is_valid,
+        get_king_coordinates, get_opposite_color, get_piece_color, get_piece_type,
+        is_getting_checked, is_insufficient_material, is_valid,
     },
+    zobrist,
 };
 use ratatui::{
     layout::{Alignment, Constraint, Direction, Layout, Rect},
@@ -17,7 +28,36 @@ use ratatui::{
     Frame,
 };
 
-#[derive(Debug)]
+// A setup piece placed on coordinates outside of the board
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OutOfBoardError(pub [i8; 2]);
+
+impl fmt::Display for OutOfBoardError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the cell {:?} is not on the board", self.0)
+    }
+}
+
+impl Error for OutOfBoardError {}
+
+// Position a game started from, kept to replay the moves from the beginning
+#[derive(Debug, Clone)]
+pub struct StartingPosition {
+    pub board: [[Option<(PieceType, PieceColor)>; 8]; 8],
+    pub player_turn: PieceColor,
+    // Moves that were never played but describe how we got to an imported position
+    // (en passant target), only used by the rules
+    pub setup_history: Vec<Move>,
+    pub castling_rights: CastlingRights,
+    pub halfmove_clock: u32,
+    pub fullmove_number: u32,
+    // Game played with the Chess960 rules, only the notation of the game tells it apart
+    pub chess960: bool,
+    // Zobrist key of the position, set when the game starts
+    pub zobrist_key: u64,
+}
+
+#[derive(Debug, Clone)]
 pub struct Board {
     pub board: [[Option<(PieceType, PieceColor)>; 8]; 8],
     pub cursor_coordinates: [i8; 2],
@@ -25,73 +65,28 @@ pub struct Board {
     pub selected_piece_cursor: i8,
     pub old_cursor_position: [i8; 2],
     pub player_turn: PieceColor,
-    pub moves_history: Vec<(Option<PieceType>, String)>,
-    pub is_pat: bool,
-    pub is_checkmate: bool,
+    pub moves_history: Vec<Move>,
+    // Zobrist key of the position reached after each move of the history
+    pub position_keys: Vec<u64>,
+    // Moves taken back with undo, the latest one is played again first by redo
+    pub undone_moves: Vec<Move>,
+    pub starting_position: StartingPosition,
+    pub castling_rights: CastlingRights,
+    // Cell the pawn of the latest move jumped over, it can be taken en passant on this move
+    pub en_passant: Option<Square>,
+    // Zobrist key of the position, updated with each move
+    pub zobrist_key: u64,
+    pub halfmove_clock: u32,
+    pub fullmove_number: u32,
+    // Ending decided outside the position: a claimed draw, a resignation, a timeout or an agreement
+    pub ending: Option<GameStatus>,
     pub is_promotion: bool,
     pub promotion_cursor: i8,
 }
 
 impl Default for Board {
     fn default() -> Self {
-        Self {
-            board: [
-                [None, None, None, None, None, None, None, None],
-                [
-                    None,
-                    None,
-                    None,
-                    None,
-                    None,
-                    None,
-                    Some((PieceType::Rook, PieceColor::Black)),
-                    None,
-                ],
-                [None, None, None, None, None, None, None, None],
-                [None, None, None, None, None, None, None, None],
-                [None, None, None, None, None, None, None, None],
-                [
-                    None,
-                    Some((PieceType::King, PieceColor::Black)),
-                    None,
-                    None,
-                    None,
-                    None,
-                    None,
-                    None,
-                ],
-                [
-                    None,
-                    None,
-                    None,
-                    None,
-                    None,
-                    None,
-                    None,
-                    Some((PieceType::King, PieceColor::White)),
-                ],
-                [
-                    None,
-                    None,
-                    None,
-                    None,
-                    None,
-                    Some((PieceType::Pawn, PieceColor::Black)),
-                    None,
-                    None,
-                ],
-            ],
-            cursor_coordinates: [4, 4],
-            selected_coordinates: [UNDEFINED_POSITION, UNDEFINED_POSITION],
-            selected_piece_cursor: 0,
-            old_cursor_position: [UNDEFINED_POSITION, UNDEFINED_POSITION],
-            player_turn: PieceColor::White,
-            moves_history: vec![],
-            is_pat: false,
-            is_checkmate: false,
-            is_promotion: false,
-            promotion_cursor: 0,
-        }
+        Self::standard()
     }
 }
 
@@ -99,9 +94,14 @@ impl Board {
     pub fn new(
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
         player_turn: PieceColor,
-        moves_history: Vec<(Option<PieceType>, String)>,
+        moves_history: Vec<Move>,
     ) -> Self {
-        Self {
+        let castling_rights = CastlingRights::from_board(board, &moves_history);
+        let en_passant = moves_history
+            .last()
+            .filter(|latest_move| latest_move.piece_color != player_turn)
+            .and_then(|latest_move| latest_move.en_passant_target());
+        let mut new_board = Self {
             board,
             cursor_coordinates: [4, 4],
             selected_coordinates: [UNDEFINED_POSITION, UNDEFINED_POSITION],
@@ -109,21 +109,152 @@ impl Board {
             old_cursor_position: [UNDEFINED_POSITION, UNDEFINED_POSITION],
             player_turn,
             moves_history,
-            is_pat: false,
-            is_checkmate: false,
+            position_keys: vec![],
+            undone_moves: vec![],
+            starting_position: StartingPosition {
+                board,
+                player_turn,
+                setup_history: vec![],
+                castling_rights,
+                halfmove_clock: 0,
+                fullmove_number: 1,
+                chess960: false,
+                zobrist_key: 0,
+            },
+            castling_rights,
+            en_passant,
+            zobrist_key: 0,
+            halfmove_clock: 0,
+            fullmove_number: 1,
+            ending: None,
             is_promotion: false,
             promotion_cursor: 0,
+        };
+        new_board.zobrist_key = new_board.compute_zobrist_key();
+        new_board.starting_position.zobrist_key = new_board.zobrist_key;
+        new_board
+    }
+
+    // Board with the standard 32 pieces starting position, white to play
+    pub fn standard() -> Self {
+        let back_rank = [
+            PieceType::Rook,
+            PieceType::Knight,
+            PieceType::Bishop,
+            PieceType::Queen,
+            PieceType::King,
+            PieceType::Bishop,
+            PieceType::Knight,
+            PieceType::Rook,
+        ];
+        Self::new(
+            Self::placement_from_back_rank(back_rank),
+            PieceColor::White,
+            vec![],
+        )
+    }
+
+    // Board with the Chess960 starting position of the given number (0 to 959), white to play
+    pub fn chess960(index: u16) -> Option<Self> {
+        let back_rank = chess960::back_rank(index)?;
+        let mut board = Self::new(
+            Self::placement_from_back_rank(back_rank),
+            PieceColor::White,
+            vec![],
+        );
+
+        // Both rooks can castle, whatever their files
+        let rooks: Vec<i8> = (0..8)
+            .filter(|&x| back_rank[x as usize] == PieceType::Rook)
+            .collect();
+        let castling_rights = CastlingRights {
+            white_king_side: Some(rooks[1]),
+            white_queen_side: Some(rooks[0]),
+            black_king_side: Some(rooks[1]),
+            black_queen_side: Some(rooks[0]),
+        };
+        board.starting_position.castling_rights = castling_rights;
+        board.starting_position.chess960 = true;
+        Some(board.starting_board())
+    }
+
+    // Pieces of both players on their back rank and pawns in front of them
+    fn placement_from_back_rank(
+        back_rank: [PieceType; 8],
+    ) -> [[Option<(PieceType, PieceColor)>; 8]; 8] {
+        let mut board = [[None; 8]; 8];
+
+        for (i, piece_type) in back_rank.iter().enumerate() {
+            board[0][i] = Some((*piece_type, PieceColor::Black));
+            board[1][i] = Some((PieceType::Pawn, PieceColor::Black));
+            board[6][i] = Some((PieceType::Pawn, PieceColor::White));
+            board[7][i] = Some((*piece_type, PieceColor::White));
         }
+        board
+    }
+
+    pub fn is_chess960(&self) -> bool {
+        self.starting_position.chess960
+    }
+
+    // Board without any piece on it, white to play
+    pub fn empty() -> Self {
+        Self::new([[None; 8]; 8], PieceColor::White, vec![])
+    }
+
+    // Board with only the given pieces placed at their [y, x] coordinates, a piece off the board
+    // is an error rather than being left out
+    pub fn from_pieces(
+        pieces: &[([i8; 2], (PieceType, PieceColor))],
+        player_turn: PieceColor,
+    ) -> Result<Self, OutOfBoardError> {
+        let mut board = [[None; 8]; 8];
+
+        for (coordinates, piece) in pieces {
+            if !is_valid(*coordinates) {
+                return Err(OutOfBoardError(*coordinates));
+            }
+            board[coordinates[0] as usize][coordinates[1] as usize] = Some(*piece);
+        }
+
+        Ok(Self::new(board, player_turn, vec![]))
+    }
+
+    // Board set back to the position the game started from
+    pub fn starting_board(&self) -> Board {
+        let start = &self.starting_position;
+        let mut board = Board::new(start.board, start.player_turn, vec![]);
+        board.starting_position = start.clone();
+        board.castling_rights = start.castling_rights;
+        board.en_passant = start
+            .setup_history
+            .last()
+            .and_then(|latest_move| latest_move.en_passant_target());
+        board.halfmove_clock = start.halfmove_clock;
+        board.fullmove_number = start.fullmove_number;
+        board.zobrist_key = board.compute_zobrist_key();
+        board.starting_position.zobrist_key = board.zobrist_key;
+        board
     }
 
     // Setters
     pub fn set_board(&mut self, board: [[Option<(PieceType, PieceColor)>; 8]; 8]) {
         self.board = board;
+        self.zobrist_key = self.compute_zobrist_key();
     }
 
     pub fn set_player_turn(&mut self, player_turn: PieceColor) {
         self.player_turn = player_turn;
+        self.zobrist_key = self.compute_zobrist_key();
     }
+
+    // History the pieces use to know about en passant
+    pub fn rules_history(&self) -> Vec<Move> {
+        let mut history = self.starting_position.setup_history.clone();
+        history.extend(self.moves_history.iter().cloned());
+        history
+    }
+
     // Check if a cell has been selected
     fn is_cell_selected(&self) -> bool {
         self.selected_coordinates[0] != UNDEFINED_POSITION
@@ -141,22 +272,25 @@ impl Board {
                 coordinates,
                 piece_color,
                 self.board,
-                self.moves_history.clone(),
-                is_getting_checked(self.board, self.player_turn, self.moves_history.clone()),
+                &self.rules_history(),
+                self.castling_rights,
             ),
             _ => Vec::new(),
         }
     }
     pub fn switch_player_turn(&mut self) {
+        // Whether en passant counts depends on the player to play
+        self.zobrist_key ^= self.en_passant_zobrist_key() ^ zobrist::side_key();
         match self.player_turn {
             PieceColor::White => self.player_turn = PieceColor::Black,
             PieceColor::Black => self.player_turn = PieceColor::White,
         }
+        self.zobrist_key ^= self.en_passant_zobrist_key();
     }
 
     // Methods to change the position of the cursor
     pub fn cursor_up(&mut self) {
-        if !self.is_checkmate && !self.is_pat && !self.is_promotion {
+        if !self.is_game_over() && !self.is_promotion {
             if self.is_cell_selected() {
                 self.move_selected_piece_cursor(false, -1)
             } else if self.cursor_coordinates[0] > 0 {
@@ -165,7 +299,7 @@ impl Board {
         }
     }
     pub fn cursor_down(&mut self) {
-        if !self.is_checkmate && !self.is_pat && !self.is_promotion {
+        if !self.is_game_over() && !self.is_promotion {
             if self.is_cell_selected() {
                 self.move_selected_piece_cursor(false, 1)
             } else if self.cursor_coordinates[0] < 7 {
@@ -182,7 +316,7 @@ impl Board {
                 3
             };
         } else {
-            if !self.is_checkmate && !self.is_pat {
+            if !self.is_game_over() {
                 if self.is_cell_selected() {
                     self.move_selected_piece_cursor(false, -1)
                 } else if self.cursor_coordinates[1] > 0 {
@@ -196,7 +330,7 @@ impl Board {
         if self.is_promotion {
             self.promotion_cursor = (self.promotion_cursor + 1) % 4;
         } else {
-            if !self.is_checkmate && !self.is_pat {
+            if !self.is_game_over() {
                 if self.is_cell_selected() {
                     self.move_selected_piece_cursor(false, 1)
                 } else if self.cursor_coordinates[1] < 7 {
@@ -207,19 +341,9 @@ impl Board {
     }
 
     pub fn did_king_already_move(&self) -> bool {
-        for i in 0..self.moves_history.len() {
-            match self.moves_history[i] {
-                (Some(piece_type), _) => {
-                    if piece_type == PieceType::King
-                        && get_player_turn_in_modulo(self.player_turn) == i % 2
-                    {
-                        return true;
-                    }
-                }
-                _ => unreachable!("Invalid move in history"),
-            }
-        }
-        false
+        self.moves_history.iter().any(|piece_move| {
+            piece_move.piece_type == PieceType::King && piece_move.piece_color == self.player_turn
+        })
     }
 
     fn move_selected_piece_cursor(&mut self, first_time_moving: bool, direction: i8) {
@@ -253,12 +377,13 @@ impl Board {
     }
 
     // Methods to select a cell on the board
+    #[allow(clippy::single_match, clippy::collapsible_match)]
     pub fn select_cell(&mut self) {
         // If we are doing a promotion the cursor is used for the popup
         if self.is_promotion {
             self.promote_piece();
         } else {
-            if !self.is_checkmate && !self.is_pat {
+            if !self.is_game_over() {
                 if !self.is_cell_selected() {
                     match get_piece_color(self.board, self.cursor_coordinates) {
                         Some(piece_color) => {
@@ -281,97 +406,373 @@ impl Board {
                             self.cursor_coordinates[0] as usize,
                             self.cursor_coordinates[1] as usize,
                         ];
-                        self.move_piece_on_the_board(selected_coords_usize, cursor_coords_usize);
+                        self.move_piece_on_the_board(
+                            selected_coords_usize,
+                            cursor_coords_usize,
+                            None,
+                        );
                         self.unselect_cell();
-                        self.switch_player_turn();
-                        self.is_pat = self.is_pat();
                     }
                 }
             }
         }
-        self.is_checkmate = self.is_checkmate();
         self.is_promotion = self.is_latest_move_promotion();
     }
 
+    // Turn the pawn of the latest move into the piece picked in the popup
     pub fn promote_piece(&mut self) {
-        if let Some(position) = self.moves_history.last() {
-            let to_y = get_int_from_char(position.1.chars().nth(2));
-            let to_x = get_int_from_char(position.1.chars().nth(3));
-
-            let new_piece = match self.promotion_cursor {
-                0 => PieceType::Queen,
-                1 => PieceType::Rook,
-                2 => PieceType::Bishop,
-                3 => PieceType::Knight,
-                _ => unreachable!("Promotion cursor out of boundaries"),
-            };
+        if let Some(latest_move) = self.moves_history.last_mut() {
+            let [to_y, to_x] = latest_move.to.coordinates().map(|i| i as usize);
+
+            let new_piece = PROMOTION_PIECES[self.promotion_cursor as usize];
+
+            if let Some((_, piece_color)) = self.board[to_y][to_x] {
+                // we replace the piece by the new piece type and keep the choice in the history
+                latest_move.promotion = Some(new_piece);
+                self.take_piece(to_y, to_x);
+                self.put_piece(to_y, to_x, Some((new_piece, piece_color)));
 
-            let current_piece_color = get_piece_color(self.board, [to_y, to_x]);
-            match current_piece_color {
-                Some(piece_color) => {
-                    // we replace the piece by the new piece type
-                    self.board[to_y as usize][to_x as usize] = Some((new_piece, piece_color));
+                if let Some(latest_key) = self.position_keys.last_mut() {
+                    *latest_key = self.zobrist_key;
                 }
-                _ => {}
             }
         }
         self.is_promotion = false;
         self.promotion_cursor = 0;
     }
 
-    pub fn move_piece_on_the_board(&mut self, from: [usize; 2], to: [usize; 2]) {
-        let direction_y: i32 = if self.player_turn == PieceColor::White {
-            -1
+    // Play the move from -> to for the player to play once it is checked to be legal, then hand
+    // the turn over. A pawn reaching the last row needs a promotion piece
+    pub fn try_move(
+        &mut self,
+        from: [i8; 2],
+        to: [i8; 2],
+        promotion: Option<PieceType>,
+    ) -> Result<MoveOutcome, IllegalMoveError> {
+        if !is_valid(from) || !is_valid(to) {
+            return Err(IllegalMoveError::OutOfBoard);
+        }
+        if self.is_game_over() {
+            return Err(IllegalMoveError::GameOver);
+        }
+        let Some((piece_type, piece_color)) = self.board[from[0] as usize][from[1] as usize] else {
+            return Err(IllegalMoveError::NoPiece);
+        };
+        if piece_color != self.player_turn {
+            return Err(IllegalMoveError::WrongSideToMove);
+        }
+
+        let is_legal = self.legal_moves().iter().any(|legal_move| {
+            legal_move.from == Square::from(from) && legal_move.to == Square::from(to)
+        });
+        if !is_legal {
+            return Err(self.illegal_move_reason(piece_type, from, to));
+        }
+
+        let promotes = piece_type == PieceType::Pawn && (to[0] == 0 || to[0] == 7);
+        match promotion {
+            None if promotes => return Err(IllegalMoveError::MissingPromotion),
+            Some(promotion) if !promotes || !PROMOTION_PIECES.contains(&promotion) => {
+                return Err(IllegalMoveError::InvalidPromotion)
+            }
+            _ => {}
+        }
+
+        self.move_piece_on_the_board(
+            [from[0] as usize, from[1] as usize],
+            [to[0] as usize, to[1] as usize],
+            promotion,
+        );
+
+        Ok(MoveOutcome {
+            piece_move: self.moves_history[self.moves_history.len() - 1],
+            is_check: is_getting_checked(self.board, self.player_turn),
+            status: self.status(),
+        })
+    }
+
+    // Why a piece of the player to play can't go from -> to
+    fn illegal_move_reason(
+        &self,
+        piece_type: PieceType,
+        from: [i8; 2],
+        to: [i8; 2],
+    ) -> IllegalMoveError {
+        let color = self.player_turn;
+        let history = self.rules_history();
+
+        // Castling fails on a piece in the way or on an attacked cell
+        let castling_side = if to[1] > from[1] {
+            CastlingSide::King
         } else {
-            1
+            CastlingSide::Queen
         };
+        if piece_type == PieceType::King
+            && self.board[to[0] as usize][to[1] as usize] == Some((PieceType::Rook, color))
+            && self.castling_rights.rook_x(color, castling_side) == Some(to[1])
+        {
+            let mut without_enemies = self.board;
+            for cell in without_enemies.iter_mut().flatten() {
+                if cell.is_some_and(|(_, piece_color)| piece_color != color) {
+                    *cell = None;
+                }
+            }
+            let path_is_free =
+                King::check_castling_condition(without_enemies, from, color, to[1], castling_side);
+            return if path_is_free {
+                IllegalMoveError::KingInCheck
+            } else {
+                IllegalMoveError::BlockedPath
+            };
+        }
 
-        let piece_type_from = get_piece_type(self.board, [from[0] as i8, from[1] as i8]);
-        let position_number: String = format!("{}{}{}{}", from[0], from[1], to[0], to[1]);
+        if !piece_type
+            .reachable_positions(from, color, self.board, &history)
+            .contains(&to.to_vec())
+        {
+            // The piece alone on the board tells a blocked path from a move it can't do
+            let mut alone = [[None; 8]; 8];
+            alone[from[0] as usize][from[1] as usize] = Some((piece_type, color));
+            return if piece_type
+                .reachable_positions(from, color, alone, &history)
+                .contains(&to.to_vec())
+            {
+                IllegalMoveError::BlockedPath
+            } else {
+                IllegalMoveError::UnreachableCell
+            };
+        }
 
-        let tuple = (piece_type_from, position_number);
+        // The piece could go there but its king would be in check
+        let mut without_piece = self.board;
+        without_piece[from[0] as usize][from[1] as usize] = None;
+        if piece_type != PieceType::King
+            && !is_getting_checked(self.board, color)
+            && is_getting_checked(without_piece, color)
+        {
+            IllegalMoveError::PinnedPiece
+        } else {
+            IllegalMoveError::KingInCheck
+        }
+    }
 
-        // We check for en passant as the latest move
-        if self.is_latest_move_en_passant(from, to) {
-            // we kill the pawn
-            let row_index = to[0] as i32 - direction_y;
+    // Play the move from -> to without checking it and hand the turn over, a pawn reaching the
+    // last row is replaced by the promotion piece if one is given, otherwise the player picks it
+    // afterwards with promote_piece. try_move is the checked way to play a move
+    pub fn move_piece_on_the_board(
+        &mut self,
+        from: [usize; 2],
+        to: [usize; 2],
+        promotion: Option<PieceType>,
+    ) {
+        let mut piece_move = self.build_move(from, to);
+        if piece_move.piece_type == PieceType::Pawn && (to[0] == 0 || to[0] == 7) {
+            piece_move.promotion = promotion;
+        }
+        self.make_move(&piece_move);
 
-            self.board[row_index as usize][to[1]] = None;
+        // A new move makes the moves taken back unreachable
+        self.undone_moves.clear();
+
+        // We store it in the history
+        self.moves_history.push(piece_move);
+        self.position_keys.push(self.zobrist_key);
+    }
+
+    // Empty the cell and give back what stood on it, the key is kept up to date
+    fn take_piece(&mut self, y: usize, x: usize) -> Option<(PieceType, PieceColor)> {
+        let piece = self.board[y][x].take();
+        if let Some((piece_type, piece_color)) = piece {
+            self.zobrist_key ^= zobrist::piece_key(piece_type, piece_color, [y as i8, x as i8]);
         }
+        piece
+    }
+
+    fn put_piece(&mut self, y: usize, x: usize, piece: Option<(PieceType, PieceColor)>) {
+        if let Some((piece_type, piece_color)) = piece {
+            self.zobrist_key ^= zobrist::piece_key(piece_type, piece_color, [y as i8, x as i8]);
+        }
+        self.board[y][x] = piece;
+    }
 
-        // We check for castling as the latest move
-        if self.is_latest_move_castling(from, to) {
-            // we set the king 2 cells on where it came from
-
-            let from_x: i32 = from[1] as i32;
-            let to_x: i32 = to[1] as i32;
-            let distance = from_x - to_x;
-            let direction_x = if distance > 0 { -1 } else { 1 };
-            let mut row_index_rook = 0;
-
-            let row_index = from[1] as i32 + direction_x * 2;
-            // We put move the king 2 cells
-            self.board[to[0]][row_index as usize] = self.board[from[0]][from[1]];
-
-            // We put the rook 3 cells from it's position if it's a big castling else 2 cells
-            // big castling
-            if distance == 4 {
-                row_index_rook = 3
-            } else if distance == -3 {
-                row_index_rook = 5
+    // Play a legal move in place, only the board state needed by the rules is changed: the
+    // history, the position keys and the selection are left as they are. The returned undo
+    // information takes the move back with unmake_move
+    pub fn make_move(&mut self, piece_move: &Move) -> MoveUndo {
+        let undo = MoveUndo {
+            captured: None,
+            castling_rights: self.castling_rights,
+            en_passant: self.en_passant,
+            halfmove_clock: self.halfmove_clock,
+            zobrist_key: self.zobrist_key,
+        };
+        let (from, to) = (piece_move.from, piece_move.to);
+        let [from_y, from_x, to_y, to_x] = [from.y, from.x, to.y, to.x].map(|i| i as usize);
+
+        // Castling and en passant are hashed again once the move is played
+        self.zobrist_key ^=
+            zobrist::castling_key(self.castling_rights) ^ self.en_passant_zobrist_key();
+
+        let captured = match piece_move.kind {
+            MoveKind::Castling(side) => {
+                let king = self.take_piece(from_y, from_x);
+                let rook = self.take_piece(to_y, to_x);
+                self.put_piece(to_y, side.king_destination_x() as usize, king);
+                self.put_piece(to_y, side.rook_destination_x() as usize, rook);
+                None
+            }
+            MoveKind::EnPassant => {
+                let pawn = self.take_piece(from_y, from_x);
+                self.put_piece(to_y, to_x, pawn);
+                self.take_piece(from_y, to_x)
+            }
+            _ => {
+                let captured = self.take_piece(to_y, to_x);
+                let piece = self
+                    .take_piece(from_y, from_x)
+                    .map(|(piece_type, color)| (piece_move.promotion.unwrap_or(piece_type), color));
+                self.put_piece(to_y, to_x, piece);
+                captured
             }
-            self.board[to[0]][row_index_rook as usize] = self.board[to[0]][to[1]];
+        };
 
-            // We remove the latest rook
-            self.board[to[0]][to[1]] = None;
+        if piece_move.piece_type == PieceType::Pawn || captured.is_some() {
+            self.halfmove_clock = 0;
         } else {
-            self.board[to[0]][to[1]] = self.board[from[0]][from[1]];
+            self.halfmove_clock += 1;
+        }
+        if piece_move.piece_color == PieceColor::Black {
+            self.fullmove_number += 1;
         }
+        self.castling_rights.update(piece_move);
+        self.en_passant = piece_move.en_passant_target();
+        self.player_turn = get_opposite_color(self.player_turn);
+        self.zobrist_key ^= zobrist::side_key()
+            ^ zobrist::castling_key(self.castling_rights)
+            ^ self.en_passant_zobrist_key();
+        debug_assert_eq!(self.zobrist_key, self.compute_zobrist_key());
+
+        MoveUndo { captured, ..undo }
+    }
 
-        self.board[from[0]][from[1]] = None;
+    // Take back a move played with make_move, the board is set back to its exact prior state
+    pub fn unmake_move(&mut self, piece_move: &Move, undo: MoveUndo) {
+        let (from, to) = (piece_move.from, piece_move.to);
+        let [from_y, from_x, to_y, to_x] = [from.y, from.x, to.y, to.x].map(|i| i as usize);
+
+        match piece_move.kind {
+            MoveKind::Castling(side) => {
+                let king = self.board[to_y][side.king_destination_x() as usize].take();
+                let rook = self.board[to_y][side.rook_destination_x() as usize].take();
+                self.board[from_y][from_x] = king;
+                self.board[to_y][to_x] = rook;
+            }
+            MoveKind::EnPassant => {
+                self.board[from_y][from_x] = self.board[to_y][to_x].take();
+                self.board[from_y][to_x] = undo.captured;
+            }
+            _ => {
+                self.board[from_y][from_x] = self.board[to_y][to_x]
+                    .take()
+                    .map(|(_, color)| (piece_move.piece_type, color));
+                self.board[to_y][to_x] = undo.captured;
+            }
+        }
 
-        // We store it in the history
-        self.moves_history.push(tuple.clone());
+        if piece_move.piece_color == PieceColor::Black {
+            self.fullmove_number -= 1;
+        }
+        self.castling_rights = undo.castling_rights;
+        self.en_passant = undo.en_passant;
+        self.halfmove_clock = undo.halfmove_clock;
+        self.player_turn = get_opposite_color(self.player_turn);
+        self.zobrist_key = undo.zobrist_key;
+        debug_assert_eq!(self.zobrist_key, self.compute_zobrist_key());
+    }
+
+    // Take back the latest move, it can be played again with redo
+    pub fn undo(&mut self) {
+        if let Some(latest_move) = self.moves_history.pop() {
+            let mut undone_moves = std::mem::take(&mut self.undone_moves);
+            undone_moves.push(latest_move);
+            self.replay(self.moves_history.clone());
+            self.undone_moves = undone_moves;
+        }
+    }
+
+    // Play again the latest move taken back with undo
+    pub fn redo(&mut self) {
+        let mut undone_moves = std::mem::take(&mut self.undone_moves);
+        if let Some(piece_move) = undone_moves.pop() {
+            let mut moves = self.moves_history.clone();
+            moves.push(piece_move);
+            self.replay(moves);
+        }
+        self.undone_moves = undone_moves;
+    }
+
+    // Set the board to the position reached by playing the moves from the starting position
+    fn replay(&mut self, moves: Vec<Move>) {
+        let mut board = self.starting_board();
+        for piece_move in moves {
+            board.move_piece_on_the_board(
+                piece_move.from.coordinates().map(|i| i as usize),
+                piece_move.to.coordinates().map(|i| i as usize),
+                piece_move.promotion,
+            );
+        }
+
+        self.board = board.board;
+        self.player_turn = board.player_turn;
+        self.moves_history = board.moves_history;
+        self.position_keys = board.position_keys;
+        self.castling_rights = board.castling_rights;
+        self.en_passant = board.en_passant;
+        self.zobrist_key = board.zobrist_key;
+        self.halfmove_clock = board.halfmove_clock;
+        self.fullmove_number = board.fullmove_number;
+
+        self.unselect_cell();
+        self.promotion_cursor = 0;
+        self.ending = None;
+        self.is_promotion = self.is_latest_move_promotion();
+    }
+
+    // Typed move of the piece at from going to to, read from the board before it is played
+    pub fn build_move(&self, from: [usize; 2], to: [usize; 2]) -> Move {
+        let (piece_type, piece_color) =
+            self.board[from[0]][from[1]].unwrap_or((PieceType::Pawn, self.player_turn));
+        let mut piece_move = Move::new(
+            piece_type,
+            piece_color,
+            from.map(|i| i as i8),
+            to.map(|i| i as i8),
+        );
+
+        // The king goes on its own rook to castle, a and h rooks or any rook in Chess960
+        if piece_type == PieceType::King {
+            piece_move.kind = match self.board[to[0]][to[1]] {
+                Some((PieceType::Rook, rook_color)) if rook_color == piece_color => {
+                    MoveKind::Castling(if to[1] > from[1] {
+                        CastlingSide::King
+                    } else {
+                        CastlingSide::Queen
+                    })
+                }
+                _ => MoveKind::Normal,
+            };
+        }
+
+        if self.is_latest_move_en_passant(from, to) {
+            piece_move.kind = MoveKind::EnPassant;
+            piece_move.captured = Some(PieceType::Pawn);
+        } else {
+            piece_move.captured = match self.board[to[0]][to[1]] {
+                Some((captured, captured_color)) if captured_color != piece_color => Some(captured),
+                _ => None,
+            };
+        }
+        piece_move
     }
 
     pub fn unselect_cell(&mut self) {
@@ -415,25 +816,46 @@ impl Board {
     }
 
     pub fn number_of_authorized_positions(&self) -> usize {
-        let mut possible_moves: Vec<Vec<i8>> = vec![];
-
-        for i in 0..8 {
-            for j in 0..8 {
-                match self.board[i][j] {
-                    Some((piece_type, piece_color)) => {
-                        if piece_color == self.player_turn {
-                            possible_moves.extend(self.get_authorized_positions(
-                                Some(piece_type),
-                                Some(piece_color),
-                                [i as i8, j as i8],
-                            ))
-                        }
-                    }
-                    _ => {}
-                }
-            }
-        }
-        possible_moves.len()
+        self.legal_moves().len()
+    }
+
+    // Every legal move of the player to play, a pawn reaching the last row gives one move for
+    // each promotion piece
+    pub fn legal_moves(&self) -> Vec<Move> {
+        self.move_generator().legal_moves()
+    }
+
+    // En passant target when a pawn of the player to play stands next to the pawn that jumped,
+    // only then does it tell positions apart
+    pub fn en_passant_capture_target(&self) -> Option<Square> {
+        let target = self.en_passant?;
+        // The pawn that jumped stands right past the target cell
+        let pawn_y = if target.y == 5 { 4 } else { 3 };
+        [target.x - 1, target.x + 1]
+            .iter()
+            .any(|&x| {
+                (0..8).contains(&x)
+                    && self.board[pawn_y][x as usize] == Some((PieceType::Pawn, self.player_turn))
+            })
+            .then_some(target)
+    }
+
+    fn move_generator(&self) -> MoveGenerator {
+        MoveGenerator::new(
+            Bitboards::from_board(&self.board),
+            self.player_turn,
+            self.en_passant,
+            self.castling_rights,
+        )
+    }
+
+    // A move is told apart by its cells and its promotion piece, the other fields are not checked
+    pub fn is_legal(&self, piece_move: &Move) -> bool {
+        self.legal_moves().iter().any(|legal_move| {
+            legal_move.from == piece_move.from
+                && legal_move.to == piece_move.to
+                && legal_move.promotion == piece_move.promotion
+        })
     }
 
     fn is_latest_move_en_passant(&self, from: [usize; 2], to: [usize; 2]) -> bool {
@@ -453,37 +875,19 @@ impl Board {
         }
     }
 
-    fn is_latest_move_castling(&self, from: [usize; 2], to: [usize; 2]) -> bool {
-        let piece_type_from = get_piece_type(self.board, [from[0] as i8, from[1] as i8]);
-        let piece_type_to = get_piece_type(self.board, [to[0] as i8, to[1] as i8]);
-
-        let from_x: i32 = from[1] as i32;
-        let to_x: i32 = to[1] as i32;
-        let distance = (from_x - to_x).abs();
-
-        match (piece_type_from, piece_type_to) {
-            (Some(PieceType::King), _) => {
-                // Check if it's a move on more
-                distance > 1
-            }
-            _ => false,
-        }
-    }
-
     fn is_latest_move_promotion(&self) -> bool {
-        if let Some(position) = self.moves_history.last() {
-            let to_y = get_int_from_char(position.1.chars().nth(2));
-            let to_x = get_int_from_char(position.1.chars().nth(3));
+        if let Some(latest_move) = self.moves_history.last() {
+            let [to_y, to_x] = latest_move.to.coordinates();
 
-            if let Some(piece_type_from) = get_piece_type(self.board, [to_y as i8, to_x as i8]) {
-                if let Some(piece_color) = get_piece_color(self.board, [to_y as i8, to_x as i8]) {
+            if let Some(piece_type_from) = get_piece_type(self.board, [to_y, to_x]) {
+                if let Some(piece_color) = get_piece_color(self.board, [to_y, to_x]) {
                     let last_row = if piece_color == PieceColor::White {
                         0
                     } else {
                         7
                     };
 
-                    if to_y as i8 == last_row && piece_type_from == PieceType::Pawn {
+                    if to_y == last_row && piece_type_from == PieceType::Pawn {
                         return true;
                     }
                 }
@@ -493,19 +897,94 @@ impl Board {
     }
 
     pub fn is_checkmate(&self) -> bool {
-        if !is_getting_checked(self.board, self.player_turn, self.moves_history.clone()) {
+        if !is_getting_checked(self.board, self.player_turn) {
             return false;
         }
 
-        self.number_of_authorized_positions() == 0
+        self.legal_moves().is_empty()
     }
 
     pub fn is_pat(&self) -> bool {
-        self.number_of_authorized_positions() == 0
+        self.legal_moves().is_empty()
+    }
+
+    // Number of times the current position was reached, the starting position included
+    pub fn repetition_count(&self) -> usize {
+        std::iter::once(&self.starting_position.zobrist_key)
+            .chain(self.position_keys.iter())
+            .filter(|key| **key == self.zobrist_key)
+            .count()
+    }
+
+    // The game is drawn once the same position comes up a third time
+    pub fn is_threefold_repetition(&self) -> bool {
+        self.repetition_count() >= 3
+    }
+
+    // Either player can claim a draw after fifty moves each without capture or pawn move
+    pub fn can_claim_fifty_move_draw(&self) -> bool {
+        self.halfmove_clock >= 100
+    }
+
+    // After seventy-five moves each without capture or pawn move the game is drawn
+    pub fn is_seventy_five_move_rule(&self) -> bool {
+        self.halfmove_clock >= 150
+    }
+
+    // Draw the game on the fifty-move rule, returns false when it can't be claimed yet. The
+    // game is already drawn once the seventy-five-move rule applies
+    pub fn claim_fifty_move_draw(&mut self) -> bool {
+        if self.can_claim_fifty_move_draw() && !self.is_game_over() && !self.is_promotion {
+            self.end_game(GameStatus::FiftyMove);
+        }
+        matches!(
+            self.status(),
+            GameStatus::FiftyMove | GameStatus::SeventyFiveMove
+        )
+    }
+
+    // End the game for a reason the position doesn't tell, like a resignation
+    pub fn end_game(&mut self, status: GameStatus) {
+        self.unselect_cell();
+        self.ending = Some(status);
     }
 
-    // Method to render the board
-    pub fn board_render(&self, area: Rect, frame: &mut Frame) {
+    // Neither side has enough pieces left to checkmate
+    pub fn is_insufficient_material(&self) -> bool {
+        is_insufficient_material(self.board)
+    }
+
+    // Whether the game goes on, and why it ended otherwise
+    pub fn status(&self) -> GameStatus {
+        if let Some(ending) = self.ending {
+            return ending;
+        }
+
+        if self.legal_moves().is_empty() {
+            if is_getting_checked(self.board, self.player_turn) {
+                GameStatus::Checkmate {
+                    winner: get_opposite_color(self.player_turn),
+                }
+            } else {
+                GameStatus::Stalemate
+            }
+        } else if self.is_threefold_repetition() {
+            GameStatus::Repetition
+        } else if self.is_seventy_five_move_rule() {
+            GameStatus::SeventyFiveMove
+        } else if self.is_insufficient_material() {
+            GameStatus::InsufficientMaterial
+        } else {
+            GameStatus::Ongoing
+        }
+    }
+
+    pub fn is_game_over(&self) -> bool {
+        self.status().is_over()
+    }
+
+    // Method to render the board, the cells of the hint move are highlighted
+    pub fn board_render(&self, area: Rect, frame: &mut Frame, hint: Option<Move>) {
         let width = area.width / 8;
         let height = area.height / 8;
         let border_height = area.height / 2 - (4 * height);
@@ -555,6 +1034,13 @@ impl Board {
                 // Color of the cell to draw the board
                 let mut cell_color: Color = if (i + j) % 2 == 0 { WHITE } else { BLACK };
 
+                // Draw the cells of the suggested move
+                if let Some(hint) = hint {
+                    if [i, j] == hint.from.coordinates() || [i, j] == hint.to.coordinates() {
+                        cell_color = Color::LightYellow
+                    }
+                }
+
                 // Draw the available moves for the selected piece
                 if self.is_cell_selected() {
                     let selected_piece_type = get_piece_type(self.board, self.selected_coordinates);
@@ -578,11 +1064,8 @@ impl Board {
                 if i == self.cursor_coordinates[0] && j == self.cursor_coordinates[1] {
                     let cell = Block::default().bg(Color::LightBlue);
                     frame.render_widget(cell.clone(), square);
-                } else if is_getting_checked(
-                    self.board,
-                    self.player_turn,
-                    self.moves_history.clone(),
-                ) && [i, j] == get_king_coordinates(self.board, self.player_turn)
+                } else if is_getting_checked(self.board, self.player_turn)
+                    && [i, j] == get_king_coordinates(self.board, self.player_turn)
                 {
                     let cell = Block::default()
                         .bg(Color::Magenta)
@@ -616,7 +1099,49 @@ impl Board {
         }
     }
 
-    pub fn history_render(&self, area: Rect, frame: &mut Frame) {
+    // SAN moves grouped by move number as (number, white move, black move),
+    // the white move is missing when black played first
+    pub fn history_rows(&self) -> Vec<(u32, Option<String>, Option<String>)> {
+        let mut rows: Vec<(u32, Option<String>, Option<String>)> = vec![];
+        let mut move_number = self.starting_position.fullmove_number;
+        let mut color = self.starting_position.player_turn;
+
+        for san in self.moves_san() {
+            match color {
+                PieceColor::White => rows.push((move_number, Some(san), None)),
+                PieceColor::Black => {
+                    match rows.last_mut() {
+                        Some((_, _, move_black)) if move_black.is_none() => *move_black = Some(san),
+                        _ => rows.push((move_number, None, Some(san))),
+                    }
+                    move_number += 1;
+                }
+            }
+            color = get_opposite_color(color);
+        }
+        rows
+    }
+
+    // Figurine of the moved piece and the rest of the SAN move
+    fn split_figurine(
+        &self,
+        entry: Option<(PieceType, String)>,
+        missing: &str,
+    ) -> (String, String) {
+        match entry {
+            Some((piece_type, san)) => {
+                let text = san.trim_start_matches(|letter| letter_to_piece(letter).is_some());
+                (
+                    format!("{} ", self.piece_type_to_utf_enum(Some(piece_type))),
+                    text.to_string(),
+                )
+            }
+            None => ("  ".to_string(), missing.to_string()),
+        }
+    }
+
+    // Method to render the history, with the explanation of the hint under it when there is one
+    pub fn history_render(&self, area: Rect, frame: &mut Frame, hint: Option<String>) {
         // We write the history board on the side
         let history_block = Block::default()
             .title("History")
@@ -627,69 +1152,639 @@ impl Board {
 
         let mut lines: Vec<Line> = vec![];
 
-        for i in (0..self.moves_history.len()).step_by(2) {
-            let piece_type_from = self.moves_history[i].0;
-            let utf_icon_white = self.piece_type_to_utf_enum(piece_type_from);
-            let number_move = &self.moves_history[i].1;
-            let move_white = convert_position_into_notation(number_move.to_string());
-
-            let mut utf_icon_black = "   ";
-            let mut move_black: String = "   ".to_string();
-
-            // If there is something for black
-            if i + 1 < self.moves_history.len() {
-                let piece_type_to = self.moves_history[i + 1].0;
-                let number = &self.moves_history[i + 1].1;
-                move_black = convert_position_into_notation(number.to_string());
-                utf_icon_black = self.piece_type_to_utf_enum(piece_type_to)
-            }
+        // The rows list the moves in the order they were played
+        let mut moved_pieces = self
+            .moves_history
+            .iter()
+            .map(|piece_move| piece_move.piece_type);
+        let mut with_piece = |san: Option<String>| {
+            san.map(|san| (moved_pieces.next().unwrap_or(PieceType::Pawn), san))
+        };
+
+        for (move_number, move_white, move_black) in self.history_rows() {
+            let (utf_icon_white, move_white) = self.split_figurine(with_piece(move_white), "...");
+            let (utf_icon_black, move_black) = self.split_figurine(with_piece(move_black), "");
 
             lines.push(Line::from(vec![
-                Span::raw(format!("{}.  ", i / 2 + 1)), // line number
-                Span::styled(format!("{} ", utf_icon_white), Style::default().fg(WHITE)), // white symbol
-                Span::raw(move_white.to_string()), // white move
-                Span::raw("     "),                // separator
-                Span::styled(
-                    format!("{} ", utf_icon_black),
-                    Style::default().fg(Color::Rgb(69, 54, 49)),
-                ), // white symbol
-                Span::raw(move_black.to_string()), // black move
+                Span::raw(format!("{:>3}.  ", move_number)), // line number
+                Span::styled(utf_icon_white, Style::default().fg(WHITE)), // white symbol
+                Span::raw(format!("{:<8}", move_white)),     // white move
+                Span::raw("  "),                             // separator
+                Span::styled(utf_icon_black, Style::default().fg(Color::Rgb(69, 54, 49))), // black symbol
+                Span::raw(format!("{:<8}", move_black)), // black move
             ]));
         }
 
-        let history_paragraph = Paragraph::new(lines).alignment(Alignment::Center);
-
         let height = area.height;
+        let hint_height = if hint.is_some() { 3 } else { 0 };
 
         let right_panel_layout = Layout::default()
             .direction(Direction::Vertical)
-            .constraints([Constraint::Length(height - 1), Constraint::Length(1)].as_ref())
+            .constraints(
+                [
+                    Constraint::Length(height.saturating_sub(4 + hint_height)),
+                    Constraint::Length(hint_height),
+                    Constraint::Length(3),
+                    Constraint::Length(1),
+                ]
+                .as_ref(),
+            )
             .split(area);
 
+        // We keep the latest moves in sight when the history gets longer than the panel
+        let visible_lines = history_block
+            .inner(right_panel_layout[0])
+            .height
+            .saturating_sub(1) as usize;
+        if lines.len() > visible_lines {
+            lines.drain(..lines.len() - visible_lines);
+        }
+
+        let history_paragraph = Paragraph::new(lines).alignment(Alignment::Center);
+
         frame.render_widget(history_block.clone(), right_panel_layout[0]);
         frame.render_widget(
             history_paragraph,
             history_block.inner(right_panel_layout[0]),
         );
 
+        // Best move found for the player to play with its line
+        if let Some(hint) = hint {
+            let hint_block = Block::default()
+                .title("Hint")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(WHITE))
+                .border_type(BorderType::Rounded);
+            let hint_paragraph = Paragraph::new(hint)
+                .block(hint_block)
+                .alignment(Alignment::Center);
+            frame.render_widget(hint_paragraph, right_panel_layout[1]);
+        }
+
+        // Moves played since the latest capture or pawn move, for the fifty-move rule
+        let clock_block = Block::default()
+            .title("Halfmove clock")
+            .borders(Borders::ALL)
+            .border_style(Style::default().fg(WHITE))
+            .border_type(BorderType::Rounded);
+        let clock_text = if self.can_claim_fifty_move_draw() && !self.is_game_over() {
+            format!("{} / 100 - Press d to claim a draw", self.halfmove_clock)
+        } else {
+            format!("{} / 100", self.halfmove_clock)
+        };
+        let clock_paragraph = Paragraph::new(clock_text)
+            .block(clock_block)
+            .alignment(Alignment::Center);
+        frame.render_widget(clock_paragraph, right_panel_layout[2]);
+
         // Bottom paragraph help text
         let text = vec![Line::from("Press h for help").alignment(Alignment::Center)];
 
         let help_paragraph = Paragraph::new(text)
             .block(Block::new())
             .alignment(Alignment::Center);
-        frame.render_widget(help_paragraph, right_panel_layout[1]);
+        frame.render_widget(help_paragraph, right_panel_layout[3]);
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use crate::{
-        board::Board,
+        board::{Board, OutOfBoardError},
+        moves::{CastlingSide, IllegalMoveError, Move, MoveKind},
         pieces::{PieceColor, PieceType},
+        san::apply_move,
+        status::GameStatus,
         utils::is_getting_checked,
     };
 
+    #[test]
+    fn default_is_standard_start() {
+        let board = Board::default();
+
+        assert_eq!(board.board, Board::standard().board);
+        assert_eq!(board.player_turn, PieceColor::White);
+        assert_eq!(
+            board.board[7][4],
+            Some((PieceType::King, PieceColor::White))
+        );
+        assert_eq!(
+            board.board[0][3],
+            Some((PieceType::Queen, PieceColor::Black))
+        );
+        assert_eq!(
+            board
+                .board
+                .iter()
+                .flatten()
+                .filter(|cell| cell.is_some())
+                .count(),
+            32
+        );
+        // 16 pawn moves and 4 knight moves
+        assert_eq!(board.number_of_authorized_positions(), 20);
+    }
+
+    #[test]
+    fn legal_moves() {
+        let board = Board::from_fen("8/P6k/8/8/8/8/8/K7 w - - 0 1").unwrap();
+        let legal_moves = board.legal_moves();
+        // 4 promotions and 3 king moves
+        assert_eq!(legal_moves.len(), 7);
+        assert!(legal_moves
+            .iter()
+            .all(|piece_move| piece_move.piece_color == PieceColor::White));
+
+        let mut promotion = Move::new(PieceType::Pawn, PieceColor::White, [1, 0], [0, 0]);
+        assert!(!board.is_legal(&promotion));
+        promotion.promotion = Some(PieceType::Knight);
+        assert!(board.is_legal(&promotion));
+        assert!(!board.is_legal(&Move::new(
+            PieceType::King,
+            PieceColor::White,
+            [7, 0],
+            [5, 0]
+        )));
+
+        // Castling and en passant come with their kind
+        let board =
+            Board::from_fen("rnbqkbnr/ppp2ppp/8/3pP3/8/8/PPPP1PPP/RNBQK2R w KQkq d6 0 4").unwrap();
+        let kinds: Vec<MoveKind> = board
+            .legal_moves()
+            .iter()
+            .map(|piece_move| piece_move.kind)
+            .collect();
+        assert!(kinds.contains(&MoveKind::EnPassant));
+        assert!(kinds.contains(&MoveKind::Castling(CastlingSide::King)));
+        assert!(kinds.contains(&MoveKind::DoublePawnPush));
+    }
+
+    #[test]
+    fn make_and_unmake_move() {
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "rnbqkbnr/ppp2ppp/8/3pP3/8/8/PPPP1PPP/RNBQK2R w KQkq d6 0 4",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 b kq - 0 1",
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+        ] {
+            let mut board = Board::from_fen(fen).unwrap();
+            let start_fen = board.to_fen();
+            for piece_move in board.legal_moves() {
+                // Playing in place gives the same position as playing through the history
+                let mut played = board.clone();
+                apply_move(
+                    &mut played,
+                    piece_move.from.coordinates(),
+                    piece_move.to.coordinates(),
+                    piece_move.promotion,
+                );
+                let undo = board.make_move(&piece_move);
+                assert_eq!(board.to_fen(), played.to_fen());
+                assert_eq!(board.en_passant, played.en_passant);
+
+                board.unmake_move(&piece_move, undo);
+                assert_eq!(board.to_fen(), start_fen);
+            }
+        }
+
+        // The captured piece comes back, en passant and promotions included
+        let mut board = Board::from_fen("4k3/1P6/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+        let en_passant = Move {
+            captured: Some(PieceType::Pawn),
+            kind: MoveKind::EnPassant,
+            ..Move::new(PieceType::Pawn, PieceColor::White, [3, 4], [2, 3])
+        };
+        let undo = board.make_move(&en_passant);
+        assert_eq!(board.board[3][3], None);
+        assert_eq!(board.en_passant, None);
+        board.unmake_move(&en_passant, undo);
+        assert_eq!(
+            board.board[3][3],
+            Some((PieceType::Pawn, PieceColor::Black))
+        );
+        assert_eq!(board.to_fen(), "4k3/1P6/8/3pP3/8/8/8/4K3 w - d6 0 1");
+
+        let mut promotion = Move::new(PieceType::Pawn, PieceColor::White, [1, 1], [0, 1]);
+        promotion.promotion = Some(PieceType::Knight);
+        let undo = board.make_move(&promotion);
+        assert_eq!(
+            board.board[0][1],
+            Some((PieceType::Knight, PieceColor::White))
+        );
+        board.unmake_move(&promotion, undo);
+        assert_eq!(
+            board.board[1][1],
+            Some((PieceType::Pawn, PieceColor::White))
+        );
+    }
+
+    #[test]
+    fn try_move() {
+        let mut board = Board::standard();
+        let outcome = board.try_move([6, 4], [4, 4], None).unwrap();
+        assert_eq!(outcome.piece_move.kind, MoveKind::DoublePawnPush);
+        assert!(!outcome.is_capture() && !outcome.is_check);
+        assert_eq!(outcome.status, GameStatus::Ongoing);
+
+        assert_eq!(
+            board.try_move([6, 3], [4, 3], None),
+            Err(IllegalMoveError::WrongSideToMove)
+        );
+        assert_eq!(
+            board.try_move([4, 0], [3, 0], None),
+            Err(IllegalMoveError::NoPiece)
+        );
+        assert_eq!(
+            board.try_move([1, 0], [8, 0], None),
+            Err(IllegalMoveError::OutOfBoard)
+        );
+        assert_eq!(
+            board.try_move([0, 5], [4, 1], None),
+            Err(IllegalMoveError::BlockedPath)
+        );
+        assert_eq!(
+            board.try_move([0, 1], [2, 1], None),
+            Err(IllegalMoveError::UnreachableCell)
+        );
+        assert_eq!(
+            board.try_move([1, 3], [2, 4], None),
+            Err(IllegalMoveError::UnreachableCell)
+        );
+
+        let outcome = board.try_move([1, 3], [3, 3], None).unwrap();
+        assert!(!outcome.is_capture());
+        let outcome = board.try_move([4, 4], [3, 3], None).unwrap();
+        assert_eq!(outcome.piece_move.captured, Some(PieceType::Pawn));
+        assert!(outcome.is_capture());
+
+        // Fool's mate
+        let mut board = Board::standard();
+        for (from, to) in [([6, 5], [5, 5]), ([1, 4], [3, 4]), ([6, 6], [4, 6])] {
+            board.try_move(from, to, None).unwrap();
+        }
+        let outcome = board.try_move([0, 3], [4, 7], None).unwrap();
+        assert!(outcome.is_check && outcome.is_checkmate() && !outcome.is_draw());
+        assert_eq!(
+            board.try_move([7, 4], [7, 5], None),
+            Err(IllegalMoveError::GameOver)
+        );
+
+        // The knight shields its king from the e7 rook and the d8 rook covers d1
+        let mut board = Board::from_fen("3rk3/4r3/8/8/8/8/4N3/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            board.try_move([6, 4], [5, 2], None),
+            Err(IllegalMoveError::PinnedPiece)
+        );
+        assert_eq!(
+            board.try_move([7, 4], [7, 3], None),
+            Err(IllegalMoveError::KingInCheck)
+        );
+
+        // Castling out of check or through a piece
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/r3K2R w K - 0 1").unwrap();
+        assert_eq!(
+            board.try_move([7, 7], [6, 7], None),
+            Err(IllegalMoveError::KingInCheck)
+        );
+        assert_eq!(
+            board.try_move([7, 4], [7, 7], None),
+            Err(IllegalMoveError::KingInCheck)
+        );
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/4KB1R w K - 0 1").unwrap();
+        assert_eq!(
+            board.try_move([7, 4], [7, 7], None),
+            Err(IllegalMoveError::BlockedPath)
+        );
+
+        let mut board = Board::from_fen("8/P6k/8/8/8/8/8/K7 w - - 0 1").unwrap();
+        assert_eq!(
+            board.try_move([1, 0], [0, 0], None),
+            Err(IllegalMoveError::MissingPromotion)
+        );
+        assert_eq!(
+            board.try_move([1, 0], [0, 0], Some(PieceType::King)),
+            Err(IllegalMoveError::InvalidPromotion)
+        );
+        assert_eq!(
+            board.try_move([7, 0], [6, 0], Some(PieceType::Queen)),
+            Err(IllegalMoveError::InvalidPromotion)
+        );
+        let outcome = board
+            .try_move([1, 0], [0, 0], Some(PieceType::Rook))
+            .unwrap();
+        assert_eq!(outcome.piece_move.promotion, Some(PieceType::Rook));
+        assert_eq!(
+            board.board[0][0],
+            Some((PieceType::Rook, PieceColor::White))
+        );
+        assert_eq!(board.player_turn, PieceColor::Black);
+    }
+
+    #[test]
+    fn history_rows_in_san() {
+        let mut board = Board::standard();
+        for (from, to) in [([6, 4], [4, 4]), ([1, 3], [3, 3]), ([4, 4], [3, 3])] {
+            apply_move(&mut board, from, to, None);
+        }
+        assert_eq!(
+            board.history_rows(),
+            vec![
+                (1, Some("e4".to_string()), Some("d5".to_string())),
+                (2, Some("exd5".to_string()), None)
+            ]
+        );
+
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 b Q - 0 12").unwrap();
+        apply_move(&mut board, [0, 4], [1, 4], None);
+        apply_move(&mut board, [7, 4], [7, 0], None);
+        assert_eq!(
+            board.history_rows(),
+            vec![
+                (12, None, Some("Ke7".to_string())),
+                (13, Some("O-O-O".to_string()), None)
+            ]
+        );
+    }
+
+    #[test]
+    fn undo_and_redo() {
+        // Capture, en passant, castling and underpromotion
+        for (fen, from, to, promotion) in [
+            (
+                "rnbqkbnr/ppp1pppp/8/3p4/4P3/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 2",
+                [4, 4],
+                [3, 3],
+                None,
+            ),
+            (
+                "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3",
+                [3, 4],
+                [2, 5],
+                None,
+            ),
+            (
+                "r3k2r/p6p/8/8/8/8/P6P/R3K2R w KQkq - 3 10",
+                [7, 4],
+                [7, 7],
+                None,
+            ),
+            (
+                "8/4P1k1/8/8/8/8/8/4K3 w - - 0 1",
+                [1, 4],
+                [0, 4],
+                Some(PieceType::Knight),
+            ),
+        ] {
+            let mut board = Board::from_fen(fen).unwrap();
+            apply_move(&mut board, from, to, promotion);
+            let played = board.to_fen();
+
+            board.undo();
+            assert_eq!(board.to_fen(), fen);
+            assert!(board.moves_history.is_empty());
+
+            board.redo();
+            assert_eq!(board.to_fen(), played);
+            assert_eq!(board.moves_history.len(), 1);
+            assert!(board.undone_moves.is_empty());
+        }
+    }
+
+    #[test]
+    fn undo_checkmate() {
+        let mut board = Board::standard();
+        for (from, to) in [
+            ([6, 5], [5, 5]),
+            ([1, 4], [3, 4]),
+            ([6, 6], [4, 6]),
+            ([0, 3], [4, 7]),
+        ] {
+            apply_move(&mut board, from, to, None);
+        }
+        assert_eq!(
+            board.status(),
+            GameStatus::Checkmate {
+                winner: PieceColor::Black
+            }
+        );
+
+        board.undo();
+        assert_eq!(board.status(), GameStatus::Ongoing);
+        assert_eq!(board.player_turn, PieceColor::Black);
+
+        // Another move drops the move taken back
+        apply_move(&mut board, [1, 0], [2, 0], None);
+        assert!(board.undone_moves.is_empty());
+        board.redo();
+        assert_eq!(board.moves_san(), vec!["f3", "e5", "g4", "a6"]);
+    }
+
+    #[test]
+    fn threefold_repetition() {
+        let mut board = Board::standard();
+        let knight_moves = [
+            ([7, 6], [5, 5]),
+            ([0, 6], [2, 5]),
+            ([5, 5], [7, 6]),
+            ([2, 5], [0, 6]),
+        ];
+        for (from, to) in knight_moves {
+            apply_move(&mut board, from, to, None);
+        }
+        assert_eq!(board.repetition_count(), 2);
+        assert!(!board.is_threefold_repetition());
+
+        for (from, to) in knight_moves {
+            apply_move(&mut board, from, to, None);
+        }
+        assert_eq!(board.repetition_count(), 3);
+        assert!(board.is_threefold_repetition());
+
+        // Taking back a move leaves the repetition
+        board.undo();
+        assert_eq!(board.status(), GameStatus::Ongoing);
+        board.redo();
+        assert_eq!(board.status(), GameStatus::Repetition);
+        assert!(board.is_game_over());
+    }
+
+    #[test]
+    fn status_follows_the_position() {
+        let mut board = Board::standard();
+        for (from, to) in [([6, 5], [5, 5]), ([1, 4], [3, 4]), ([6, 6], [4, 6])] {
+            apply_move(&mut board, from, to, None);
+        }
+        assert_eq!(board.status(), GameStatus::Ongoing);
+
+        // The status asked for before the mate is not kept once the queen moved
+        let mate = board.build_move([0, 3], [4, 7]);
+        let undo = board.make_move(&mate);
+        assert_eq!(
+            board.status(),
+            GameStatus::Checkmate {
+                winner: PieceColor::Black
+            }
+        );
+        board.unmake_move(&mate, undo);
+        assert_eq!(board.status(), GameStatus::Ongoing);
+        assert_eq!(board.repetition_count(), 1);
+    }
+
+    #[test]
+    fn chess960_castling() {
+        let board = Board::chess960(518).unwrap();
+        assert_eq!(board.to_fen(), Board::standard().to_fen());
+        assert!(board.is_chess960());
+        assert!(Board::chess960(960).is_none());
+
+        // The king on b1 goes to c1 and the rook on a1 to d1
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/RK5R w HA - 0 1").unwrap();
+        assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/RK5R w KQ - 0 1");
+        apply_move(&mut board, [7, 1], [7, 0], None);
+        assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/2KR3R b - - 1 1");
+        assert_eq!(board.moves_san(), vec!["O-O-O"]);
+
+        // The king and the rook swap their cells
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/5KR1 w G - 0 1").unwrap();
+        apply_move(&mut board, [7, 5], [7, 6], None);
+        assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/5RK1 b - - 1 1");
+        assert_eq!(board.moves_san(), vec!["O-O"]);
+
+        // The king would be taken by the queen once the rook left b1
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/qRK5 w B - 0 1").unwrap();
+        let positions =
+            board.get_authorized_positions(Some(PieceType::King), Some(PieceColor::White), [7, 2]);
+        assert!(!positions.contains(&vec![7, 1]));
+    }
+
+    #[test]
+    fn castling_rights() {
+        // The rooks and b1 being attacked doesn't prevent castling
+        let board = Board::from_fen("1r2k2r/8/8/8/8/8/8/R3K2R w KQ - 0 1").unwrap();
+        let positions =
+            board.get_authorized_positions(Some(PieceType::King), Some(PieceColor::White), [7, 4]);
+        assert!(positions.contains(&vec![7, 0]));
+        assert!(positions.contains(&vec![7, 7]));
+
+        // A rook taken on h1 and replaced by another one doesn't give the right back
+        let mut board = Board::from_fen("4k3/8/8/8/7R/8/P5b1/4K2R b K - 0 1").unwrap();
+        apply_move(&mut board, [6, 6], [7, 7], None);
+        apply_move(&mut board, [4, 7], [7, 7], None);
+        apply_move(&mut board, [0, 4], [0, 3], None);
+        let positions =
+            board.get_authorized_positions(Some(PieceType::King), Some(PieceColor::White), [7, 4]);
+        assert!(!positions.contains(&vec![7, 7]));
+        assert_eq!(board.to_fen(), "3k4/8/8/8/8/8/P7/4K2R w - - 1 3");
+    }
+
+    #[test]
+    fn fifty_and_seventy_five_move_rules() {
+        let mut board = Board::from_fen("8/8/8/4k3/8/8/4P3/R3K3 w - - 99 80").unwrap();
+        assert!(!board.can_claim_fifty_move_draw());
+        assert!(!board.claim_fifty_move_draw());
+
+        apply_move(&mut board, [7, 0], [6, 0], None);
+        assert_eq!(board.halfmove_clock, 100);
+        assert!(board.can_claim_fifty_move_draw());
+        assert!(!board.is_seventy_five_move_rule());
+
+        // A pawn move starts the count again
+        apply_move(&mut board, [3, 4], [3, 3], None);
+        apply_move(&mut board, [6, 4], [4, 4], None);
+        assert_eq!(board.halfmove_clock, 0);
+        assert!(!board.can_claim_fifty_move_draw());
+
+        let mut board = Board::from_fen("8/8/8/4k3/8/8/8/R3K3 w - - 149 120").unwrap();
+        apply_move(&mut board, [7, 0], [6, 0], None);
+        assert!(board.is_seventy_five_move_rule());
+        assert_eq!(board.status(), GameStatus::SeventyFiveMove);
+        assert!(board.claim_fifty_move_draw());
+        assert!(board.is_game_over());
+        assert_eq!(board.result_token(), "1/2-1/2");
+    }
+
+    #[test]
+    fn insufficient_material() {
+        for fen in [
+            "8/8/4k3/8/8/3K4/8/8 w - - 0 1",
+            "8/8/4k3/8/8/3K4/5B2/8 w - - 0 1",
+            "8/8/4k3/8/8/3K4/5n2/8 w - - 0 1",
+            "2b5/8/4k3/8/8/3K4/8/7B w - - 0 1",
+        ] {
+            assert!(
+                Board::from_fen(fen).unwrap().is_insufficient_material(),
+                "{fen}"
+            );
+        }
+        for fen in [
+            "8/8/4k3/8/8/3K4/5P2/8 w - - 0 1",
+            "8/8/4k3/8/8/3K4/4NN2/8 w - - 0 1",
+            "1b6/8/4k3/8/8/3K4/8/7B w - - 0 1",
+            "8/8/4k3/8/8/3K4/4Bn2/8 w - - 0 1",
+            "8/8/4k3/8/8/3K4/8/7r w - - 0 1",
+        ] {
+            assert!(
+                !Board::from_fen(fen).unwrap().is_insufficient_material(),
+                "{fen}"
+            );
+        }
+
+        // Taking the last pawn ends the game
+        let mut board = Board::from_fen("8/8/4k3/8/4p3/3K4/8/8 w - - 0 1").unwrap();
+        board.cursor_coordinates = [5, 3];
+        board.select_cell();
+        board.cursor_coordinates = [4, 4];
+        board.select_cell();
+        assert_eq!(board.status(), GameStatus::InsufficientMaterial);
+        assert!(board.is_game_over());
+    }
+
+    #[test]
+    fn empty_board() {
+        let board = Board::empty();
+
+        assert!(board.board.iter().flatten().all(|cell| cell.is_none()));
+    }
+
+    #[test]
+    fn from_pieces_places_pieces() {
+        let board = Board::from_pieces(
+            &[
+                ([7, 4], (PieceType::King, PieceColor::White)),
+                ([0, 4], (PieceType::King, PieceColor::Black)),
+                ([1, 0], (PieceType::Rook, PieceColor::White)),
+            ],
+            PieceColor::Black,
+        )
+        .unwrap();
+
+        assert_eq!(board.player_turn, PieceColor::Black);
+        assert_eq!(
+            board.board[1][0],
+            Some((PieceType::Rook, PieceColor::White))
+        );
+        assert_eq!(
+            board
+                .board
+                .iter()
+                .flatten()
+                .filter(|cell| cell.is_some())
+                .count(),
+            3
+        );
+
+        // A typo in the coordinates is not silently dropped
+        assert_eq!(
+            Board::from_pieces(
+                &[([8, 4], (PieceType::King, PieceColor::White))],
+                PieceColor::White
+            )
+            .unwrap_err(),
+            OutOfBoardError([8, 4])
+        );
+    }
+
     #[test]
     fn is_getting_checked_true() {
         let custom_board = [
@@ -723,7 +1818,7 @@ mod tests {
         let mut board = Board::default();
         board.set_board(custom_board);
 
-        assert!(is_getting_checked(custom_board, PieceColor::White, vec![]));
+        assert!(is_getting_checked(custom_board, PieceColor::White));
     }
 
     #[test]
@@ -768,7 +1863,7 @@ mod tests {
         let mut board = Board::default();
         board.set_board(custom_board);
 
-        assert!(!is_getting_checked(custom_board, PieceColor::White, vec![]));
+        assert!(!is_getting_checked(custom_board, PieceColor::White));
     }
 
     #[test]
@@ -822,7 +1917,7 @@ mod tests {
         let mut board = Board::default();
         board.set_board(custom_board);
 
-        assert!(!is_getting_checked(custom_board, PieceColor::Black, vec![]));
+        assert!(!is_getting_checked(custom_board, PieceColor::Black));
     }
 
     #[test]
@@ -885,7 +1980,7 @@ mod tests {
         let mut board = Board::default();
         board.set_board(custom_board);
 
-        assert!(!is_getting_checked(custom_board, PieceColor::Black, vec![]));
+        assert!(!is_getting_checked(custom_board, PieceColor::Black));
     }
 
     #[test]
@@ -1168,7 +2263,12 @@ mod tests {
         let board = Board::new(
             custom_board,
             PieceColor::Black,
-            vec![(Some(PieceType::Pawn), "7363".to_string())],
+            vec![Move::new(
+                PieceType::Pawn,
+                PieceColor::White,
+                [7, 3],
+                [6, 3],
+            )],
         );
 
         assert_eq!(Board::is_latest_move_promotion(&board), false);
@@ -1215,7 +2315,12 @@ mod tests {
         let board = Board::new(
             custom_board,
             PieceColor::Black,
-            vec![(Some(PieceType::Pawn), "1404".to_string())],
+            vec![Move::new(
+                PieceType::Pawn,
+                PieceColor::White,
+                [1, 4],
+                [0, 4],
+            )],
         );
 
         assert_eq!(Board::is_latest_move_promotion(&board), true);
@@ -1265,17 +2370,36 @@ mod tests {
         assert_eq!(board.is_latest_move_promotion(), false);
 
         // Move the pawn to a promote cell
-        board.move_piece_on_the_board([1, 4], [0, 4]);
+        board.move_piece_on_the_board([1, 4], [0, 4], None);
         assert_eq!(board.is_latest_move_promotion(), true);
 
         // Promote the pawn
         board.promote_piece();
+        assert_eq!(
+            board.moves_history.last().unwrap().promotion,
+            Some(PieceType::Queen)
+        );
+        assert_eq!(board.moves_san(), vec!["e8=Q#".to_string()]);
 
         // The black king gets checkmated
-        board.player_turn = PieceColor::Black;
+        assert_eq!(board.player_turn, PieceColor::Black);
         assert_eq!(board.is_checkmate(), true);
     }
 
+    #[test]
+    fn underpromotion_in_history() {
+        let mut board = Board::from_fen("8/4P1k1/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        apply_move(&mut board, [1, 4], [0, 4], Some(PieceType::Knight));
+
+        assert_eq!(
+            board.board[0][4],
+            Some((PieceType::Knight, PieceColor::White))
+        );
+        assert_eq!(board.moves_history[0].promotion, Some(PieceType::Knight));
+        assert_eq!(board.moves_san(), vec!["e8=N+".to_string()]);
+        assert_eq!(board.is_latest_move_promotion(), false);
+    }
+
     #[test]
     fn is_promote_true_black() {
         let custom_board = [
@@ -1318,7 +2442,12 @@ mod tests {
         let board = Board::new(
             custom_board,
             PieceColor::White,
-            vec![(Some(PieceType::Pawn), "6474".to_string())],
+            vec![Move::new(
+                PieceType::Pawn,
+                PieceColor::Black,
+                [6, 4],
+                [7, 4],
+            )],
         );
 
         assert_eq!(board.is_latest_move_promotion(), true);
@@ -1368,14 +2497,14 @@ mod tests {
         assert_eq!(board.is_latest_move_promotion(), false);
 
         // Move the pawn to a promote cell
-        board.move_piece_on_the_board([6, 5], [7, 5]);
+        board.move_piece_on_the_board([6, 5], [7, 5], None);
         assert_eq!(board.is_latest_move_promotion(), true);
 
         // Promote the pawn
         board.promote_piece();
 
         // The black king gets checkmated
-        board.player_turn = PieceColor::White;
+        assert_eq!(board.player_turn, PieceColor::White);
         assert_eq!(board.is_pat(), true);
     }
-}
\ No newline at end of file
+}
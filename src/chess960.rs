@@ -0,0 +1,124 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+};
+
+use crate::pieces::PieceType;
+
+// Chess960 starting positions are numbered from 0 to 959 (Scharnagl numbering)
+pub const POSITION_COUNT: u16 = 960;
+// Number of the standard starting position, RNBQKBNR
+pub const STANDARD_POSITION: u16 = 518;
+
+// Cells the two knights take among the five cells left once the bishops and the queen are placed
+const KNIGHT_CELLS: [(usize, usize); 10] = [
+    (0, 1),
+    (0, 2),
+    (0, 3),
+    (0, 4),
+    (1, 2),
+    (1, 3),
+    (1, 4),
+    (2, 3),
+    (2, 4),
+    (3, 4),
+];
+
+// Back rank of the starting position with the given number, from the a file to the h file
+pub fn back_rank(index: u16) -> Option<[PieceType; 8]> {
+    if index >= POSITION_COUNT {
+        return None;
+    }
+    let mut cells: [Option<PieceType>; 8] = [None; 8];
+    let mut n = index as usize;
+
+    // One bishop on each color, first the one on b, d, f or h then the one on a, c, e or g
+    cells[2 * (n % 4) + 1] = Some(PieceType::Bishop);
+    n /= 4;
+    cells[2 * (n % 4)] = Some(PieceType::Bishop);
+    n /= 4;
+
+    // The queen on one of the six empty cells, the knights on two of the five left
+    let empty_cells: Vec<usize> = (0..8).filter(|&x| cells[x].is_none()).collect();
+    cells[empty_cells[n % 6]] = Some(PieceType::Queen);
+    n /= 6;
+
+    let empty_cells: Vec<usize> = (0..8).filter(|&x| cells[x].is_none()).collect();
+    let (first_knight, second_knight) = KNIGHT_CELLS[n];
+    cells[empty_cells[first_knight]] = Some(PieceType::Knight);
+    cells[empty_cells[second_knight]] = Some(PieceType::Knight);
+
+    // The king always stands between the two rooks
+    let empty_cells: Vec<usize> = (0..8).filter(|&x| cells[x].is_none()).collect();
+    cells[empty_cells[0]] = Some(PieceType::Rook);
+    cells[empty_cells[1]] = Some(PieceType::King);
+    cells[empty_cells[2]] = Some(PieceType::Rook);
+
+    Some(cells.map(|cell| cell.unwrap_or(PieceType::Pawn)))
+}
+
+// Number of a starting position picked at random
+pub fn random_index() -> u16 {
+    let random = RandomState::new().build_hasher().finish();
+    (random % POSITION_COUNT as u64) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        chess960::{back_rank, random_index, POSITION_COUNT, STANDARD_POSITION},
+        pieces::PieceType,
+    };
+
+    #[test]
+    fn back_ranks() {
+        assert_eq!(
+            back_rank(STANDARD_POSITION),
+            Some([
+                PieceType::Rook,
+                PieceType::Knight,
+                PieceType::Bishop,
+                PieceType::Queen,
+                PieceType::King,
+                PieceType::Bishop,
+                PieceType::Knight,
+                PieceType::Rook,
+            ])
+        );
+        assert_eq!(
+            back_rank(0),
+            Some([
+                PieceType::Bishop,
+                PieceType::Bishop,
+                PieceType::Queen,
+                PieceType::Knight,
+                PieceType::Knight,
+                PieceType::Rook,
+                PieceType::King,
+                PieceType::Rook,
+            ])
+        );
+        assert_eq!(back_rank(POSITION_COUNT), None);
+        assert!(random_index() < POSITION_COUNT);
+
+        // Every position is different and follows the rules
+        let mut back_ranks = vec![];
+        for index in 0..POSITION_COUNT {
+            let rank = back_rank(index).unwrap();
+            let find = |piece_type: PieceType| -> Vec<usize> {
+                (0..8).filter(|&x| rank[x] == piece_type).collect()
+            };
+            let (bishops, rooks, king) = (
+                find(PieceType::Bishop),
+                find(PieceType::Rook),
+                find(PieceType::King),
+            );
+            assert_ne!(bishops[0] % 2, bishops[1] % 2);
+            assert!(rooks[0] < king[0] && king[0] < rooks[1]);
+            back_ranks.push(rank);
+        }
+        back_ranks.sort_by_key(|rank| format!("{:?}", rank));
+        back_ranks.dedup();
+        assert_eq!(back_ranks.len(), POSITION_COUNT as usize);
+    }
+}
@@ -0,0 +1,537 @@
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    board::Board,
+    evaluation::piece_value,
+    moves::Move,
+    pieces::PieceColor,
+    uci::{UciEngine, UciError},
+    utils::is_getting_checked,
+};
+
+// Scores are in centipawns, a mate found n plies ahead scores MATE - n
+pub const MATE: i32 = 100_000;
+const INFINITY: i32 = MATE + 1;
+// Any score past this one is a mate
+const MATE_BOUND: i32 = MATE - 1_000;
+
+// The clock is looked at once every this many nodes
+const NODES_BETWEEN_CLOCK_CHECKS: u64 = 1024;
+
+// The search stops at whichever comes first
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SearchLimits {
+    pub max_depth: u32,
+    pub time: Duration,
+}
+
+impl Default for SearchLimits {
+    fn default() -> Self {
+        Self {
+            max_depth: 64,
+            time: Duration::from_millis(1000),
+        }
+    }
+}
+
+// Outcome of the deepest iteration the search went through, the score is from the side of the
+// player to play
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    pub best_move: Option<Move>,
+    pub score: i32,
+    pub depth: u32,
+    pub principal_variation: Vec<Move>,
+    pub nodes: u64,
+}
+
+impl SearchResult {
+    // Number of moves of the player to play before mating, negative when getting mated
+    pub fn mate_in(&self) -> Option<i32> {
+        if self.score.abs() < MATE_BOUND {
+            return None;
+        }
+        let plies = MATE - self.score.abs();
+        Some(self.score.signum() * (plies + 1) / 2)
+    }
+
+    // Score for the player to play, in pawns or as a mate, followed by the best line in SAN
+    // played from the board searched
+    pub fn explanation(&self, board: &Board) -> String {
+        let score = match self.mate_in() {
+            Some(moves) if moves > 0 => format!("Mate in {}", moves),
+            Some(moves) => format!("Mated in {}", -moves),
+            None => format!("{:+.2}", f64::from(self.score) / 100.0),
+        };
+        format!(
+            "{}: {}",
+            score,
+            board.line_san(&self.principal_variation).join(" ")
+        )
+    }
+}
+
+struct Searcher<'a> {
+    board: Board,
+    // No deadline when the search goes on until it is told to stop
+    deadline: Option<Instant>,
+    stop: &'a AtomicBool,
+    stopped: bool,
+    nodes: u64,
+    // Best move found in each position, tried first when the position comes up again
+    best_moves: HashMap<u64, Move>,
+    // Keys of the positions of the game and of the line being searched, for repetitions
+    path: Vec<u64>,
+}
+
+impl Searcher<'_> {
+    fn is_time_over(&mut self) -> bool {
+        if self.nodes.is_multiple_of(NODES_BETWEEN_CLOCK_CHECKS)
+            && (self.stop.load(Ordering::Relaxed)
+                || self
+                    .deadline
+                    .is_some_and(|deadline| Instant::now() >= deadline))
+        {
+            self.stopped = true;
+        }
+        self.stopped
+    }
+
+    fn is_draw(&self) -> bool {
+        let key = self.board.zobrist_key;
+        self.board.halfmove_clock >= 100 || self.path[..self.path.len() - 1].contains(&key)
+    }
+
+    // Score of a move to search the likely best ones first: the best move found before, then the
+    // captures of the most valuable pieces by the least valuable ones, then the promotions
+    fn move_order(&self, piece_move: &Move, best_move: Option<&Move>) -> i32 {
+        if best_move == Some(piece_move) {
+            return INFINITY;
+        }
+        let capture = piece_move.captured.map_or(0, |captured| {
+            10_000 + 10 * piece_value(captured) - piece_value(piece_move.piece_type)
+        });
+        capture + piece_move.promotion.map_or(0, piece_value)
+    }
+
+    fn ordered_moves(&self, mut legal_moves: Vec<Move>) -> Vec<Move> {
+        let best_move = self.best_moves.get(&self.board.zobrist_key);
+        legal_moves.sort_by_cached_key(|piece_move| -self.move_order(piece_move, best_move));
+        legal_moves
+    }
+
+    fn side_evaluation(&self) -> i32 {
+        match self.board.player_turn {
+            PieceColor::White => self.board.evaluate(),
+            PieceColor::Black => -self.board.evaluate(),
+        }
+    }
+
+    fn play(&mut self, piece_move: &Move, depth: u32, ply: i32, alpha: i32, beta: i32) -> i32 {
+        let undo = self.board.make_move(piece_move);
+        self.path.push(self.board.zobrist_key);
+        let score = if depth == 0 {
+            -self.quiescence(-beta, -alpha)
+        } else {
+            -self.alpha_beta(depth, ply + 1, -beta, -alpha)
+        };
+        self.path.pop();
+        self.board.unmake_move(piece_move, undo);
+        score
+    }
+
+    fn alpha_beta(&mut self, depth: u32, ply: i32, mut alpha: i32, beta: i32) -> i32 {
+        self.nodes += 1;
+        if self.is_time_over() {
+            return 0;
+        }
+        if ply > 0 && self.is_draw() {
+            return 0;
+        }
+
+        let legal_moves = self.board.legal_moves();
+        if legal_moves.is_empty() {
+            return if is_getting_checked(self.board.board, self.board.player_turn) {
+                -MATE + ply
+            } else {
+                0
+            };
+        }
+
+        let mut best_score = -INFINITY;
+        let mut best_move = None;
+        for piece_move in self.ordered_moves(legal_moves) {
+            let score = self.play(&piece_move, depth - 1, ply, alpha, beta);
+            if self.stopped {
+                return 0;
+            }
+            if score > best_score {
+                best_score = score;
+                best_move = Some(piece_move);
+            }
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        if let Some(best_move) = best_move {
+            self.best_moves.insert(self.board.zobrist_key, best_move);
+        }
+        best_score
+    }
+
+    // Captures and promotions are played until the position is quiet, so that the evaluation
+    // doesn't stop in the middle of an exchange
+    fn quiescence(&mut self, mut alpha: i32, beta: i32) -> i32 {
+        self.nodes += 1;
+        if self.is_time_over() {
+            return 0;
+        }
+
+        let stand_pat = self.side_evaluation();
+        if stand_pat >= beta {
+            return stand_pat;
+        }
+        alpha = alpha.max(stand_pat);
+
+        let mut noisy_moves = self.board.legal_moves();
+        noisy_moves.retain(|piece_move| piece_move.is_capture() || piece_move.promotion.is_some());
+        for piece_move in self.ordered_moves(noisy_moves) {
+            let undo = self.board.make_move(&piece_move);
+            let score = -self.quiescence(-beta, -alpha);
+            self.board.unmake_move(&piece_move, undo);
+            if self.stopped {
+                return 0;
+            }
+            if score >= beta {
+                return score;
+            }
+            alpha = alpha.max(score);
+        }
+        alpha
+    }
+
+    // Line of best moves from the position, followed until it goes back to a position seen
+    fn principal_variation(&mut self, depth: u32) -> Vec<Move> {
+        let mut board = self.board.clone();
+        let mut seen = vec![board.zobrist_key];
+        let mut line = vec![];
+        while let Some(piece_move) = self.best_moves.get(&board.zobrist_key).copied() {
+            if line.len() >= depth as usize || !board.is_legal(&piece_move) {
+                break;
+            }
+            board.make_move(&piece_move);
+            line.push(piece_move);
+            if seen.contains(&board.zobrist_key) {
+                break;
+            }
+            seen.push(board.zobrist_key);
+        }
+        line
+    }
+}
+
+// Alpha-beta search with iterative deepening: each depth is searched in turn, starting with the
+// best line of the previous one, until the time or the depth limit is reached
+pub fn search(board: &Board, limits: SearchLimits) -> SearchResult {
+    search_with_reports(board, limits, &AtomicBool::new(false), |_| {})
+}
+
+// Search that can also be stopped from another thread, the result of each depth is reported as
+// soon as it is searched
+pub fn search_with_reports(
+    board: &Board,
+    limits: SearchLimits,
+    stop: &AtomicBool,
+    mut report: impl FnMut(&SearchResult),
+) -> SearchResult {
+    let legal_moves = board.legal_moves();
+    let mut path: Vec<u64> = vec![board.starting_position.zobrist_key];
+    path.extend(board.position_keys.iter());
+    if path.last() != Some(&board.zobrist_key) {
+        path.push(board.zobrist_key);
+    }
+    let mut searcher = Searcher {
+        board: board.clone(),
+        deadline: Instant::now().checked_add(limits.time),
+        stop,
+        stopped: false,
+        nodes: 0,
+        best_moves: HashMap::new(),
+        path,
+    };
+
+    let mut result = SearchResult {
+        best_move: legal_moves.first().copied(),
+        score: 0,
+        depth: 0,
+        principal_variation: legal_moves.first().copied().into_iter().collect(),
+        nodes: 0,
+    };
+    // A forced move is searched once, for its score and its line
+    let max_depth = match legal_moves.len() {
+        0 => 0,
+        1 => limits.max_depth.min(1),
+        _ => limits.max_depth,
+    };
+    for depth in 1..=max_depth {
+        let score = searcher.alpha_beta(depth, 0, -INFINITY, INFINITY);
+        if searcher.stopped {
+            break;
+        }
+        let principal_variation = searcher.principal_variation(depth);
+        result = SearchResult {
+            best_move: principal_variation.first().copied().or(result.best_move),
+            score,
+            depth,
+            principal_variation,
+            nodes: searcher.nodes,
+        };
+        report(&result);
+        // Nothing shorter can be found once a mate is seen
+        if score.abs() >= MATE_BOUND {
+            break;
+        }
+    }
+    result.nodes = searcher.nodes;
+    result
+}
+
+// Move found by the computer, or why the external engine couldn't give one
+pub type ComputerAnswer = Result<SearchResult, UciError>;
+
+// Computer opponent, it searches on its own thread so that the interface keeps rendering. The
+// moves come from an external UCI engine when one is given
+#[derive(Debug)]
+pub struct ComputerPlayer {
+    pub color: PieceColor,
+    pub limits: SearchLimits,
+    uci_engine: Option<Arc<Mutex<UciEngine>>>,
+    // Search going on, with the number of moves and the key of the position it started from
+    thinking: Option<(usize, u64, mpsc::Receiver<ComputerAnswer>)>,
+    // Set to stop the search going on, each search is given its own flag
+    stop: Arc<AtomicBool>,
+}
+
+impl ComputerPlayer {
+    pub fn new(color: PieceColor, limits: SearchLimits) -> Self {
+        Self {
+            color,
+            limits,
+            uci_engine: None,
+            thinking: None,
+            stop: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    // The engine is given the search time of the limits for every move
+    pub fn with_uci_engine(
+        color: PieceColor,
+        limits: SearchLimits,
+        uci_engine: Arc<Mutex<UciEngine>>,
+    ) -> Self {
+        let mut computer = Self::new(color, limits);
+        computer.uci_engine = Some(uci_engine);
+        computer
+    }
+
+    pub fn is_thinking(&self) -> bool {
+        self.thinking.is_some()
+    }
+
+    // Start searching the position, the search goes on while the interface keeps running. A
+    // search already going on is stopped
+    pub fn think(&mut self, board: &Board) {
+        self.cancel();
+        self.stop = Arc::new(AtomicBool::new(false));
+        let (sender, receiver) = mpsc::channel();
+        let position = board.clone();
+        let limits = self.limits;
+        let uci_engine = self.uci_engine.clone();
+        let stop = self.stop.clone();
+        thread::spawn(move || {
+            let result = match uci_engine {
+                Some(uci_engine) => match uci_engine.lock() {
+                    Ok(mut uci_engine) => uci_engine.best_move(&position, limits.time),
+                    Err(_) => Err(UciError::Exited),
+                },
+                None => Ok(search_with_reports(&position, limits, &stop, |_| {})),
+            };
+            // The receiver is gone when the game changed in the meantime
+            let _ = sender.send(result);
+        });
+        self.thinking = Some((board.moves_history.len(), board.zobrist_key, receiver));
+    }
+
+    // Result of the search once it is over, a search started from another position is dropped
+    pub fn poll(&mut self, board: &Board) -> Option<ComputerAnswer> {
+        let (move_count, key, receiver) = self.thinking.as_ref()?;
+        let result = match receiver.try_recv() {
+            Ok(result) => Some(result),
+            Err(mpsc::TryRecvError::Empty) => return None,
+            Err(mpsc::TryRecvError::Disconnected) => None,
+        };
+        let is_same_position =
+            *move_count == board.moves_history.len() && *key == board.zobrist_key;
+        self.thinking = None;
+        result.filter(|_| is_same_position)
+    }
+
+    // Stop the search going on, its result is never given
+    pub fn cancel(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        self.thinking = None;
+    }
+}
+
+impl Drop for ComputerPlayer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        sync::Arc,
+        time::{Duration, Instant},
+    };
+
+    use crate::{
+        board::Board,
+        engine::{search, ComputerPlayer, SearchLimits, SearchResult},
+        moves::Move,
+        pieces::{PieceColor, PieceType},
+        uci::parse_uci_move,
+    };
+
+    fn limits(max_depth: u32) -> SearchLimits {
+        SearchLimits {
+            max_depth,
+            time: Duration::from_secs(60),
+        }
+    }
+
+    #[test]
+    fn finds_mates_and_captures() {
+        // Back rank mate
+        let board = Board::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").unwrap();
+        let result = search(&board, limits(3));
+        assert_eq!(result.best_move.unwrap().to_string(), "a1a8");
+        assert_eq!(result.mate_in(), Some(1));
+
+        // The hanging queen is taken
+        let board = Board::from_fen("4k3/8/8/3q4/8/8/3R4/4K3 w - - 0 1").unwrap();
+        let result = search(&board, limits(3));
+        assert_eq!(result.best_move.unwrap().to_string(), "d2d5");
+        assert!(result.score > 400);
+        assert_eq!(result.principal_variation[0], result.best_move.unwrap());
+
+        // The defended pawn is left alone, quiescence sees the queen would be lost
+        let board = Board::from_fen("4k3/8/2p5/3p4/8/8/8/3QK3 w - - 0 1").unwrap();
+        let result = search(&board, limits(1));
+        assert_ne!(result.best_move.unwrap().to_string(), "d1d5");
+
+        // Black mates too
+        let board = Board::from_fen("r5k1/8/8/8/8/8/5PPP/6K1 b - - 0 1").unwrap();
+        let result = search(&board, limits(3));
+        assert_eq!(result.best_move.unwrap().to_string(), "a8a1");
+        assert_eq!(result.best_move.unwrap().piece_type, PieceType::Rook);
+    }
+
+    #[test]
+    fn game_over_positions() {
+        let board = Board::from_fen("6k1/5ppp/8/8/8/8/5PPP/r5K1 w - - 0 1").unwrap();
+        assert_eq!(search(&board, limits(3)).best_move, None);
+    }
+
+    #[test]
+    fn explanation() {
+        let board = Board::standard();
+        let line = ["e2e4", "e7e5", "g1f3"];
+        let mut position = board.clone();
+        let principal_variation: Vec<Move> = line
+            .iter()
+            .map(|notation| {
+                let piece_move = parse_uci_move(&position, notation, false).unwrap();
+                position.make_move(&piece_move);
+                piece_move
+            })
+            .collect();
+        let result = SearchResult {
+            best_move: Some(principal_variation[0]),
+            score: 35,
+            depth: 3,
+            principal_variation,
+            nodes: 0,
+        };
+        assert_eq!(result.explanation(&board), "+0.35: e4 e5 Nf3");
+
+        let board = Board::from_fen("6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1").unwrap();
+        let result = search(&board, limits(3));
+        assert_eq!(result.explanation(&board), "Mate in 1: Ra8#");
+    }
+
+    #[test]
+    fn forced_move() {
+        // Taking the queen is the only move, it is still searched for its score
+        let board = Board::from_fen("k7/8/8/8/7R/8/1q6/K7 w - - 0 1").unwrap();
+        assert_eq!(board.legal_moves().len(), 1);
+        let result = search(&board, limits(5));
+        assert_eq!(result.best_move.unwrap().to_string(), "a1b2");
+        assert_eq!(result.depth, 1);
+        assert_eq!(result.principal_variation, vec![result.best_move.unwrap()]);
+        assert!(result.score > 400);
+    }
+
+    #[test]
+    fn computer_player() {
+        let board = Board::standard();
+        let mut computer = ComputerPlayer::new(PieceColor::White, limits(2));
+        computer.think(&board);
+        assert!(computer.is_thinking());
+
+        let started = Instant::now();
+        let result = loop {
+            if let Some(result) = computer.poll(&board) {
+                break result.unwrap();
+            }
+            assert!(started.elapsed() < Duration::from_secs(30));
+            std::thread::sleep(Duration::from_millis(5));
+        };
+        assert!(!computer.is_thinking());
+        assert!(board.is_legal(&result.best_move.unwrap()));
+
+        // A search from a position left behind is dropped
+        computer.think(&board);
+        let mut board = board;
+        board.try_move([6, 4], [4, 4], None).unwrap();
+        while computer.is_thinking() {
+            assert_eq!(computer.poll(&board), None);
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+    #[test]
+    fn cancelled_search() {
+        let board = Board::standard();
+        let mut computer = ComputerPlayer::new(PieceColor::White, limits(64));
+        computer.think(&board);
+        computer.cancel();
+        assert!(!computer.is_thinking());
+
+        // The search thread lets its flag go once it stopped
+        let started = Instant::now();
+        while Arc::strong_count(&computer.stop) > 1 {
+            assert!(started.elapsed() < Duration::from_secs(5));
+            std::thread::sleep(Duration::from_millis(5));
+        }
+    }
+}
@@ -0,0 +1,295 @@
+use crate::{
+    bitboard::{
+        bishop_attacks, color_index, king_attacks, knight_attacks, pawn_attacks, rook_attacks,
+        square_coordinates, squares, Bitboard, Bitboards, FILE_A,
+    },
+    board::Board,
+    pieces::{PieceColor, PieceType},
+    utils::get_opposite_color,
+};
+
+// Piece-square tables from white's side, written from the a8 cell to the h1 cell like the board
+#[rustfmt::skip]
+const PAWN_TABLE: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+    50, 50, 50, 50, 50, 50, 50, 50,
+    10, 10, 20, 30, 30, 20, 10, 10,
+     5,  5, 10, 25, 25, 10,  5,  5,
+     0,  0,  0, 20, 20,  0,  0,  0,
+     5, -5,-10,  0,  0,-10, -5,  5,
+     5, 10, 10,-20,-20, 10, 10,  5,
+     0,  0,  0,  0,  0,  0,  0,  0,
+];
+#[rustfmt::skip]
+const KNIGHT_TABLE: [i32; 64] = [
+    -50,-40,-30,-30,-30,-30,-40,-50,
+    -40,-20,  0,  0,  0,  0,-20,-40,
+    -30,  0, 10, 15, 15, 10,  0,-30,
+    -30,  5, 15, 20, 20, 15,  5,-30,
+    -30,  0, 15, 20, 20, 15,  0,-30,
+    -30,  5, 10, 15, 15, 10,  5,-30,
+    -40,-20,  0,  5,  5,  0,-20,-40,
+    -50,-40,-30,-30,-30,-30,-40,-50,
+];
+#[rustfmt::skip]
+const BISHOP_TABLE: [i32; 64] = [
+    -20,-10,-10,-10,-10,-10,-10,-20,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -10,  0,  5, 10, 10,  5,  0,-10,
+    -10,  5,  5, 10, 10,  5,  5,-10,
+    -10,  0, 10, 10, 10, 10,  0,-10,
+    -10, 10, 10, 10, 10, 10, 10,-10,
+    -10,  5,  0,  0,  0,  0,  5,-10,
+    -20,-10,-10,-10,-10,-10,-10,-20,
+];
+#[rustfmt::skip]
+const ROOK_TABLE: [i32; 64] = [
+     0,  0,  0,  0,  0,  0,  0,  0,
+     5, 10, 10, 10, 10, 10, 10,  5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+    -5,  0,  0,  0,  0,  0,  0, -5,
+     0,  0,  0,  5,  5,  0,  0,  0,
+];
+#[rustfmt::skip]
+const QUEEN_TABLE: [i32; 64] = [
+    -20,-10,-10, -5, -5,-10,-10,-20,
+    -10,  0,  0,  0,  0,  0,  0,-10,
+    -10,  0,  5,  5,  5,  5,  0,-10,
+     -5,  0,  5,  5,  5,  5,  0, -5,
+      0,  0,  5,  5,  5,  5,  0, -5,
+    -10,  5,  5,  5,  5,  5,  0,-10,
+    -10,  0,  5,  0,  0,  0,  0,-10,
+    -20,-10,-10, -5, -5,-10,-10,-20,
+];
+#[rustfmt::skip]
+const KING_TABLE: [i32; 64] = [
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -30,-40,-40,-50,-50,-40,-40,-30,
+    -20,-30,-30,-40,-40,-30,-30,-20,
+    -10,-20,-20,-20,-20,-20,-20,-10,
+     20, 20,  0,  0,  0,  0, 20, 20,
+     20, 30, 10,  0,  0, 10, 30, 20,
+];
+
+pub fn piece_value(piece_type: PieceType) -> i32 {
+    match piece_type {
+        PieceType::Pawn => 100,
+        PieceType::Knight => 320,
+        PieceType::Bishop => 330,
+        PieceType::Rook => 500,
+        PieceType::Queen => 900,
+        PieceType::King => 0,
+    }
+}
+
+fn square_value(piece_type: PieceType, color: PieceColor, square: usize) -> i32 {
+    let table = match piece_type {
+        PieceType::Pawn => &PAWN_TABLE,
+        PieceType::Knight => &KNIGHT_TABLE,
+        PieceType::Bishop => &BISHOP_TABLE,
+        PieceType::Rook => &ROOK_TABLE,
+        PieceType::Queen => &QUEEN_TABLE,
+        PieceType::King => &KING_TABLE,
+    };
+    // The tables are read upside down for black
+    match color {
+        PieceColor::White => table[square],
+        PieceColor::Black => table[square ^ 56],
+    }
+}
+
+// Value of each cell a piece reaches without landing on its own pieces or on a cell an enemy pawn
+// guards
+fn mobility_weight(piece_type: PieceType) -> i32 {
+    match piece_type {
+        PieceType::Knight | PieceType::Bishop => 4,
+        PieceType::Rook => 2,
+        PieceType::Queen => 1,
+        PieceType::Pawn | PieceType::King => 0,
+    }
+}
+
+const DOUBLED_PAWN: i32 = -12;
+const ISOLATED_PAWN: i32 = -15;
+// Bonus of a passed pawn by the number of rows it went up
+const PASSED_PAWN: [i32; 6] = [0, 10, 20, 35, 60, 100];
+const KING_SHIELD_PAWN: i32 = 10;
+const KING_ZONE_ATTACK: i32 = -8;
+
+fn file(x: i8) -> Bitboard {
+    FILE_A << x
+}
+
+fn adjacent_files(x: i8) -> Bitboard {
+    let left = if x > 0 { file(x - 1) } else { 0 };
+    let right = if x < 7 { file(x + 1) } else { 0 };
+    left | right
+}
+
+// Rows in front of the given row for the pawns of a color
+fn rows_ahead(y: i8, color: PieceColor) -> Bitboard {
+    match color {
+        PieceColor::White => (1 << (y * 8)) - 1,
+        PieceColor::Black if y < 7 => !((1 << ((y + 1) * 8)) - 1),
+        PieceColor::Black => 0,
+    }
+}
+
+fn material_and_mobility(bitboards: &Bitboards, color: PieceColor) -> i32 {
+    let occupied = bitboards.occupied();
+    let own = bitboards.colors[color_index(color)];
+    let enemy = get_opposite_color(color);
+    let guarded = pawn_attacks(bitboards.get(PieceType::Pawn, enemy), enemy);
+
+    let mut score = 0;
+    for piece_type in [
+        PieceType::Pawn,
+        PieceType::Knight,
+        PieceType::Bishop,
+        PieceType::Rook,
+        PieceType::Queen,
+        PieceType::King,
+    ] {
+        for square in squares(bitboards.get(piece_type, color)) {
+            let reach = match piece_type {
+                PieceType::Knight => knight_attacks(square),
+                PieceType::Bishop => bishop_attacks(square, occupied),
+                PieceType::Rook => rook_attacks(square, occupied),
+                PieceType::Queen => {
+                    bishop_attacks(square, occupied) | rook_attacks(square, occupied)
+                }
+                PieceType::Pawn | PieceType::King => 0,
+            };
+            score += piece_value(piece_type)
+                + square_value(piece_type, color, square)
+                + mobility_weight(piece_type) * (reach & !own & !guarded).count_ones() as i32;
+        }
+    }
+    score
+}
+
+// Doubled and isolated pawns are weaknesses, passed pawns grow stronger as they go up
+fn pawn_structure(bitboards: &Bitboards, color: PieceColor) -> i32 {
+    let pawns = bitboards.get(PieceType::Pawn, color);
+    let enemy_pawns = bitboards.get(PieceType::Pawn, get_opposite_color(color));
+
+    let mut score = 0;
+    for x in 0..8 {
+        let on_file = (pawns & file(x)).count_ones() as i32;
+        if on_file > 1 {
+            score += DOUBLED_PAWN * (on_file - 1);
+        }
+        if pawns & adjacent_files(x) == 0 {
+            score += ISOLATED_PAWN * on_file;
+        }
+    }
+    for square in squares(pawns) {
+        let [y, x] = square_coordinates(square);
+        if enemy_pawns & rows_ahead(y, color) & (file(x) | adjacent_files(x)) == 0 {
+            let advance = match color {
+                PieceColor::White => 6 - y,
+                PieceColor::Black => y - 1,
+            };
+            score += PASSED_PAWN[advance.clamp(0, 5) as usize];
+        }
+    }
+    score
+}
+
+// Pawns in front of the king and cells around it the enemy attacks, while the enemy queen can
+// still lead an attack
+fn king_safety(bitboards: &Bitboards, color: PieceColor) -> i32 {
+    let enemy = get_opposite_color(color);
+    let Some(king) = bitboards.king_square(color) else {
+        return 0;
+    };
+    if bitboards.get(PieceType::Queen, enemy) == 0 {
+        return 0;
+    }
+    let zone = king_attacks(king);
+    let shield = zone
+        & rows_ahead(square_coordinates(king)[0], color)
+        & bitboards.get(PieceType::Pawn, color);
+    let attacked = zone & bitboards.attacked_cells(enemy, bitboards.occupied());
+    KING_SHIELD_PAWN * shield.count_ones() as i32 + KING_ZONE_ATTACK * attacked.count_ones() as i32
+}
+
+impl Board {
+    // Static score of the position in centipawns, positive when white is better: material,
+    // piece placement, mobility, pawn structure and king safety
+    pub fn evaluate(&self) -> i32 {
+        let bitboards = Bitboards::from_board(&self.board);
+        let side = |color| {
+            material_and_mobility(&bitboards, color)
+                + pawn_structure(&bitboards, color)
+                + king_safety(&bitboards, color)
+        };
+        side(PieceColor::White) - side(PieceColor::Black)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bitboard::Bitboards,
+        board::Board,
+        evaluation::{king_safety, pawn_structure},
+        pieces::PieceColor,
+    };
+
+    fn bitboards(fen: &str) -> Bitboards {
+        Bitboards::from_board(&Board::from_fen(fen).unwrap().board)
+    }
+
+    #[test]
+    fn evaluation() {
+        assert_eq!(Board::standard().evaluate(), 0);
+        // Mirrored positions have opposite scores
+        let white = Board::from_fen("4k3/8/8/8/3N4/8/8/4K3 w - - 0 1").unwrap();
+        let black = Board::from_fen("4k3/8/8/3n4/8/8/8/4K3 w - - 0 1").unwrap();
+        assert!(white.evaluate() > 300);
+        assert_eq!(white.evaluate(), -black.evaluate());
+
+        let kiwipete = "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1";
+        let mirrored = "r3k2r/pppbbppp/2n2q1P/1P2p3/3pn3/BN2PNP1/P1PPQPB1/R3K2R b KQkq - 0 1";
+        assert_eq!(
+            Board::from_fen(kiwipete).unwrap().evaluate(),
+            -Board::from_fen(mirrored).unwrap().evaluate()
+        );
+
+        // A bishop with open diagonals is worth more than one blocked by its own pawns
+        let open = Board::from_fen("4k3/8/8/8/8/8/1P2P3/2B1K3 w - - 0 1").unwrap();
+        let blocked = Board::from_fen("4k3/8/8/8/8/8/1P1P4/2B1K3 w - - 0 1").unwrap();
+        assert!(open.evaluate() > blocked.evaluate());
+    }
+
+    #[test]
+    fn pawns() {
+        // Doubled and isolated on the e file, e3 went up one row without any pawn in front
+        let board = bitboards("4k3/8/8/8/8/4P3/4P3/4K3 w - - 0 1");
+        assert_eq!(pawn_structure(&board, PieceColor::White), -12 - 2 * 15 + 10);
+
+        // Each pawn stops the other one from being passed
+        let board = bitboards("4k3/3p4/8/4P3/8/8/8/4K3 w - - 0 1");
+        assert_eq!(pawn_structure(&board, PieceColor::White), -15);
+        assert_eq!(pawn_structure(&board, PieceColor::Black), -15);
+
+        // A passed pawn about to promote
+        let board = bitboards("4k3/8/8/8/8/8/p7/4K3 w - - 0 1");
+        assert_eq!(pawn_structure(&board, PieceColor::Black), -15 + 100);
+    }
+
+    #[test]
+    fn king_shelter() {
+        // Three pawns in front of the king, and the queen attacks f1
+        let board = bitboards("6k1/5ppp/8/8/8/8/5PPP/q5K1 w - - 0 1");
+        assert_eq!(king_safety(&board, PieceColor::White), 3 * 10 - 8);
+        // Nothing to fear without the enemy queen
+        assert_eq!(king_safety(&board, PieceColor::Black), 0);
+    }
+}
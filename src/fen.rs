@@ -0,0 +1,602 @@
+use std::{error::Error, fmt};
+
+use crate::{
+    board::Board,
+    moves::{home_line, CastlingRights, CastlingSide, Move},
+    pieces::{PieceColor, PieceType},
+    utils::{coordinates_to_notation, notation_to_coordinates},
+};
+
+pub const STARTING_POSITION_FEN: &str = "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1";
+
+// Castling sides in FEN order with the FEN letter for white
+const CASTLING_SIDES: [(CastlingSide, char); 2] =
+    [(CastlingSide::King, 'K'), (CastlingSide::Queen, 'Q')];
+
+type Placement = [[Option<(PieceType, PieceColor)>; 8]; 8];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FenError {
+    WrongFieldCount(usize),
+    WrongRankCount(usize),
+    InvalidRank(String),
+    InvalidPiece(char),
+    InvalidKingCount(PieceColor),
+    InvalidSideToMove(String),
+    InvalidCastling(String),
+    InvalidEnPassant(String),
+    InvalidHalfmoveClock(String),
+    InvalidFullmoveNumber(String),
+}
+
+impl fmt::Display for FenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FenError::WrongFieldCount(count) => {
+                write!(f, "expected 4 to 6 fields, found {}", count)
+            }
+            FenError::WrongRankCount(count) => write!(f, "expected 8 ranks, found {}", count),
+            FenError::InvalidRank(rank) => write!(f, "rank '{}' does not have 8 files", rank),
+            FenError::InvalidPiece(piece) => write!(f, "unknown piece '{}'", piece),
+            FenError::InvalidKingCount(color) => {
+                write!(f, "{:?} must have exactly one king", color)
+            }
+            FenError::InvalidSideToMove(side) => write!(f, "invalid side to move '{}'", side),
+            FenError::InvalidCastling(castling) => {
+                write!(f, "invalid castling rights '{}'", castling)
+            }
+            FenError::InvalidEnPassant(square) => {
+                write!(f, "invalid en passant square '{}'", square)
+            }
+            FenError::InvalidHalfmoveClock(clock) => {
+                write!(f, "invalid halfmove clock '{}'", clock)
+            }
+            FenError::InvalidFullmoveNumber(number) => {
+                write!(f, "invalid fullmove number '{}'", number)
+            }
+        }
+    }
+}
+
+impl Error for FenError {}
+
+pub fn piece_to_fen_char(piece_type: PieceType, piece_color: PieceColor) -> char {
+    let letter = match piece_type {
+        PieceType::Pawn => 'p',
+        PieceType::Rook => 'r',
+        PieceType::Bishop => 'b',
+        PieceType::Queen => 'q',
+        PieceType::King => 'k',
+        PieceType::Knight => 'n',
+    };
+    match piece_color {
+        PieceColor::White => letter.to_ascii_uppercase(),
+        PieceColor::Black => letter,
+    }
+}
+
+pub fn fen_char_to_piece(letter: char) -> Option<(PieceType, PieceColor)> {
+    let piece_type = match letter.to_ascii_lowercase() {
+        'p' => PieceType::Pawn,
+        'r' => PieceType::Rook,
+        'b' => PieceType::Bishop,
+        'q' => PieceType::Queen,
+        'k' => PieceType::King,
+        'n' => PieceType::Knight,
+        _ => return None,
+    };
+    let piece_color = if letter.is_ascii_uppercase() {
+        PieceColor::White
+    } else {
+        PieceColor::Black
+    };
+    Some((piece_type, piece_color))
+}
+
+fn castling_letter(color: PieceColor, letter: char) -> char {
+    match color {
+        PieceColor::White => letter,
+        PieceColor::Black => letter.to_ascii_lowercase(),
+    }
+}
+
+// Column of the king on its home line, it can only castle from there
+fn home_king_x(board: &Placement, color: PieceColor) -> Option<i8> {
+    let line = home_line(color) as usize;
+    (0..8).find(|&x| board[line][x as usize] == Some((PieceType::King, color)))
+}
+
+// Rook the side of the king ends with, the one K and Q stand for
+fn outermost_rook_x(
+    board: &Placement,
+    color: PieceColor,
+    king_x: i8,
+    side: CastlingSide,
+) -> Option<i8> {
+    let line = home_line(color) as usize;
+    let is_rook = |x: &i8| board[line][*x as usize] == Some((PieceType::Rook, color));
+    match side {
+        CastlingSide::King => (king_x + 1..8).rev().find(is_rook),
+        CastlingSide::Queen => (0..king_x).find(is_rook),
+    }
+}
+
+fn parse_placement(placement: &str) -> Result<Placement, FenError> {
+    let ranks: Vec<&str> = placement.split('/').collect();
+    if ranks.len() != 8 {
+        return Err(FenError::WrongRankCount(ranks.len()));
+    }
+
+    let mut board = [[None; 8]; 8];
+    for (i, rank) in ranks.iter().enumerate() {
+        let mut j = 0;
+        for letter in rank.chars() {
+            if let Some(empty_cells) = letter.to_digit(10) {
+                if empty_cells == 0 || empty_cells > 8 {
+                    return Err(FenError::InvalidRank(rank.to_string()));
+                }
+                j += empty_cells as usize;
+            } else {
+                let piece = fen_char_to_piece(letter).ok_or(FenError::InvalidPiece(letter))?;
+                if j >= 8 {
+                    return Err(FenError::InvalidRank(rank.to_string()));
+                }
+                board[i][j] = Some(piece);
+                j += 1;
+            }
+            if j > 8 {
+                return Err(FenError::InvalidRank(rank.to_string()));
+            }
+        }
+        if j != 8 {
+            return Err(FenError::InvalidRank(rank.to_string()));
+        }
+    }
+
+    for color in [PieceColor::White, PieceColor::Black] {
+        let kings = board
+            .iter()
+            .flatten()
+            .filter(|cell| **cell == Some((PieceType::King, color)))
+            .count();
+        if kings != 1 {
+            return Err(FenError::InvalidKingCount(color));
+        }
+    }
+
+    Ok(board)
+}
+
+impl Board {
+    // Build a board from a Forsyth-Edwards Notation string
+    pub fn from_fen(fen: &str) -> Result<Board, FenError> {
+        let fields: Vec<&str> = fen.split_whitespace().collect();
+        if !(4..=6).contains(&fields.len()) {
+            return Err(FenError::WrongFieldCount(fields.len()));
+        }
+
+        let board = parse_placement(fields[0])?;
+
+        let player_turn = match fields[1] {
+            "w" => PieceColor::White,
+            "b" => PieceColor::Black,
+            side => return Err(FenError::InvalidSideToMove(side.to_string())),
+        };
+
+        // K and Q stand for the outermost rooks, a file letter for the rook on that file
+        // (Shredder-FEN and X-FEN for Chess960)
+        let castling = fields[2];
+        let invalid_castling = || FenError::InvalidCastling(castling.to_string());
+        let mut castling_rights = CastlingRights::default();
+        // Rook files, or a king or a rook away from its usual cell, are only found in Chess960
+        let mut chess960 = false;
+        if castling != "-" {
+            for letter in castling.chars() {
+                let color = if letter.is_ascii_uppercase() {
+                    PieceColor::White
+                } else {
+                    PieceColor::Black
+                };
+                let king_x = home_king_x(&board, color).ok_or_else(invalid_castling)?;
+                let (side, rook_x) = match letter.to_ascii_uppercase() {
+                    'K' => (
+                        CastlingSide::King,
+                        outermost_rook_x(&board, color, king_x, CastlingSide::King),
+                    ),
+                    'Q' => (
+                        CastlingSide::Queen,
+                        outermost_rook_x(&board, color, king_x, CastlingSide::Queen),
+                    ),
+                    file @ 'A'..='H' => {
+                        chess960 = true;
+                        let x = file as i8 - 'A' as i8;
+                        let side = if x > king_x {
+                            CastlingSide::King
+                        } else {
+                            CastlingSide::Queen
+                        };
+                        let is_rook = board[home_line(color) as usize][x as usize]
+                            == Some((PieceType::Rook, color));
+                        (side, Some(x).filter(|_| is_rook))
+                    }
+                    _ => return Err(invalid_castling()),
+                };
+                if castling_rights.has(color, side) {
+                    return Err(invalid_castling());
+                }
+                let rook_x = rook_x.ok_or_else(invalid_castling)?;
+                chess960 |= king_x != 4 || rook_x != side.rook_x();
+                castling_rights.set(color, side, Some(rook_x));
+            }
+        }
+
+        // The history the rules read has to tell which pawn can be taken en passant
+        let mut setup_history: Vec<Move> = vec![];
+
+        // The en passant target is the cell the enemy pawn jumped over on the latest move
+        let en_passant = fields[3];
+        if en_passant != "-" {
+            let invalid = || FenError::InvalidEnPassant(en_passant.to_string());
+            let [y, x] = notation_to_coordinates(en_passant).ok_or_else(invalid)?;
+            let (target_y, pawn_y, start_y, pawn_color) = match player_turn {
+                PieceColor::White => (2, 3, 1, PieceColor::Black),
+                PieceColor::Black => (5, 4, 6, PieceColor::White),
+            };
+            if y != target_y
+                || board[y as usize][x as usize].is_some()
+                || board[start_y as usize][x as usize].is_some()
+                || board[pawn_y as usize][x as usize] != Some((PieceType::Pawn, pawn_color))
+            {
+                return Err(invalid());
+            }
+            setup_history.push(Move::new(
+                PieceType::Pawn,
+                pawn_color,
+                [start_y, x],
+                [pawn_y, x],
+            ));
+        }
+
+        let halfmove_clock = match fields.get(4) {
+            Some(clock) => clock
+                .parse::<u32>()
+                .map_err(|_| FenError::InvalidHalfmoveClock(clock.to_string()))?,
+            None => 0,
+        };
+        let fullmove_number = match fields.get(5) {
+            Some(number) => match number.parse::<u32>() {
+                Ok(number) if number > 0 => number,
+                _ => return Err(FenError::InvalidFullmoveNumber(number.to_string())),
+            },
+            None => 1,
+        };
+
+        let mut new_board = Board::new(board, player_turn, vec![]);
+        new_board.starting_position.setup_history = setup_history;
+        new_board.starting_position.castling_rights = castling_rights;
+        new_board.starting_position.halfmove_clock = halfmove_clock;
+        new_board.starting_position.fullmove_number = fullmove_number;
+        new_board.starting_position.chess960 = chess960;
+        Ok(new_board.starting_board())
+    }
+
+    // Forsyth-Edwards Notation of the current position
+    pub fn to_fen(&self) -> String {
+        format!(
+            "{} {} {} {} {} {}",
+            self.placement_fen(),
+            side_to_move_fen(self.player_turn),
+            self.castling_fen(),
+            self.en_passant_fen(),
+            self.halfmove_clock,
+            self.fullmove_number
+        )
+    }
+
+    fn placement_fen(&self) -> String {
+        let mut placement: Vec<String> = vec![];
+        for row in self.board {
+            let mut rank = String::new();
+            let mut empty_cells = 0;
+            for cell in row {
+                match cell {
+                    Some((piece_type, piece_color)) => {
+                        if empty_cells > 0 {
+                            rank += &empty_cells.to_string();
+                            empty_cells = 0;
+                        }
+                        rank.push(piece_to_fen_char(piece_type, piece_color));
+                    }
+                    None => empty_cells += 1,
+                }
+            }
+            if empty_cells > 0 {
+                rank += &empty_cells.to_string();
+            }
+            placement.push(rank);
+        }
+        placement.join("/")
+    }
+
+    // X-FEN castling rights: K and Q when the rook is the outermost one, its file otherwise
+    fn castling_fen(&self) -> String {
+        let mut castling = String::new();
+        for color in [PieceColor::White, PieceColor::Black] {
+            let Some(king_x) = home_king_x(&self.board, color) else {
+                continue;
+            };
+            for (side, letter) in CASTLING_SIDES {
+                if let Some(rook_x) = self.castling_rights.rook_x(color, side) {
+                    let letter =
+                        if outermost_rook_x(&self.board, color, king_x, side) == Some(rook_x) {
+                            letter
+                        } else {
+                            (b'A' + rook_x as u8) as char
+                        };
+                    castling.push(castling_letter(color, letter));
+                }
+            }
+        }
+        if castling.is_empty() {
+            castling.push('-');
+        }
+        castling
+    }
+
+    fn en_passant_fen(&self) -> String {
+        match self.en_passant {
+            Some(target) => coordinates_to_notation(target.coordinates()),
+            None => "-".to_string(),
+        }
+    }
+}
+
+fn side_to_move_fen(player_turn: PieceColor) -> &'static str {
+    match player_turn {
+        PieceColor::White => "w",
+        PieceColor::Black => "b",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        board::Board,
+        fen::{FenError, STARTING_POSITION_FEN},
+        pieces::{PieceColor, PieceType},
+    };
+
+    #[test]
+    fn standard_position_round_trip() {
+        let board = Board::from_fen(STARTING_POSITION_FEN).unwrap();
+
+        assert_eq!(board.board, Board::standard().board);
+        assert_eq!(board.player_turn, PieceColor::White);
+        assert_eq!(Board::standard().to_fen(), STARTING_POSITION_FEN);
+        assert_eq!(board.to_fen(), STARTING_POSITION_FEN);
+    }
+
+    #[test]
+    fn to_fen_after_moves() {
+        let mut board = Board::standard();
+        board.move_piece_on_the_board([6, 4], [4, 4], None);
+        assert_eq!(
+            board.to_fen(),
+            "rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1"
+        );
+
+        board.move_piece_on_the_board([0, 6], [2, 5], None);
+        assert_eq!(
+            board.to_fen(),
+            "rnbqkb1r/pppppppp/5n2/8/4P3/8/PPPP1PPP/RNBQKBNR w KQkq - 1 2"
+        );
+
+        board.move_piece_on_the_board([7, 4], [6, 4], None);
+        assert_eq!(
+            board.to_fen(),
+            "rnbqkb1r/pppppppp/5n2/8/4P3/8/PPPPKPPP/RNBQ1BNR b kq - 2 2"
+        );
+    }
+
+    #[test]
+    fn round_trip_custom_positions() {
+        for fen in [
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w Kq f6 0 3",
+            "4k3/8/8/8/3pP3/8/8/4K3 b - e3 0 40",
+        ] {
+            assert_eq!(Board::from_fen(fen).unwrap().to_fen(), fen);
+        }
+    }
+
+    #[test]
+    fn missing_fields_default_clocks() {
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 b -").unwrap_err();
+        assert_eq!(board, FenError::WrongFieldCount(3));
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/4K3 b - -").unwrap();
+        assert_eq!(board.halfmove_clock, 0);
+        assert_eq!(board.fullmove_number, 1);
+    }
+
+    #[test]
+    fn chess960_castling_rights() {
+        // Shredder-FEN files, written back in X-FEN
+        let board =
+            Board::from_fen("bqnbrkrn/pppppppp/8/8/8/8/PPPPPPPP/BQNBRKRN w GEge - 0 1").unwrap();
+        assert_eq!(
+            board.to_fen(),
+            "bqnbrkrn/pppppppp/8/8/8/8/PPPPPPPP/BQNBRKRN w KQkq - 0 1"
+        );
+
+        // A rook that isn't the outermost one keeps its file
+        let fen = "4k3/8/8/8/8/8/8/R3KR1R w FA - 0 1";
+        let board = Board::from_fen(fen).unwrap();
+        assert_eq!(board.castling_rights.white_king_side, Some(5));
+        assert_eq!(board.to_fen(), "4k3/8/8/8/8/8/8/R3KR1R w FQ - 0 1");
+        assert_eq!(
+            Board::from_fen(&board.to_fen()).unwrap().to_fen(),
+            board.to_fen()
+        );
+
+        assert!(board.is_chess960());
+
+        // Only the castling rights tell a Chess960 position apart
+        for (fen, chess960) in [
+            (
+                "bqnbrkrn/pppppppp/8/8/8/8/PPPPPPPP/BQNBRKRN w KQkq - 0 1",
+                true,
+            ),
+            ("4k3/8/8/8/8/8/8/R3K2R w HA - 0 1", true),
+            ("4k3/8/8/8/8/8/8/R3K2R w KQ - 0 1", false),
+            ("4k3/8/8/8/8/8/8/R3K1R1 w Q - 0 1", false),
+            ("4k3/8/8/8/8/8/8/2RK3R w - - 0 1", false),
+        ] {
+            assert_eq!(
+                Board::from_fen(fen).unwrap().is_chess960(),
+                chess960,
+                "{}",
+                fen
+            );
+        }
+
+        assert_eq!(
+            Board::from_fen("4k3/8/8/8/8/8/8/R3K2R w KH - 0 1").unwrap_err(),
+            FenError::InvalidCastling("KH".to_string())
+        );
+        assert_eq!(
+            Board::from_fen("4k3/8/8/8/8/8/8/R3K2R w G - 0 1").unwrap_err(),
+            FenError::InvalidCastling("G".to_string())
+        );
+    }
+
+    #[test]
+    fn imported_castling_rights() {
+        let board = Board::from_fen("r3k2r/p6p/8/8/8/8/P6P/R3K2R w Qk - 0 1").unwrap();
+
+        let mut white_king = PieceType::King.authorized_positions(
+            [7, 4],
+            PieceColor::White,
+            board.board,
+            &board.rules_history(),
+            board.castling_rights,
+        );
+        white_king.sort();
+        // Only the big castle is left
+        assert_eq!(
+            white_king,
+            vec![
+                vec![6, 3],
+                vec![6, 4],
+                vec![6, 5],
+                vec![7, 0],
+                vec![7, 3],
+                vec![7, 5]
+            ]
+        );
+
+        let mut black_king = PieceType::King.authorized_positions(
+            [0, 4],
+            PieceColor::Black,
+            board.board,
+            &board.rules_history(),
+            board.castling_rights,
+        );
+        black_king.sort();
+        // Only the small castle is left
+        assert_eq!(
+            black_king,
+            vec![
+                vec![0, 3],
+                vec![0, 5],
+                vec![0, 7],
+                vec![1, 3],
+                vec![1, 4],
+                vec![1, 5]
+            ]
+        );
+    }
+
+    #[test]
+    fn imported_en_passant() {
+        let board =
+            Board::from_fen("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3")
+                .unwrap();
+
+        let mut positions = PieceType::Pawn.authorized_positions(
+            [3, 4],
+            PieceColor::White,
+            board.board,
+            &board.rules_history(),
+            board.castling_rights,
+        );
+        positions.sort();
+        assert_eq!(positions, vec![vec![2, 4], vec![2, 5]]);
+
+        // Playing en passant removes the pawn that jumped
+        let mut board = board;
+        board.move_piece_on_the_board([3, 4], [2, 5], None);
+        assert_eq!(board.board[3][5], None);
+        assert_eq!(
+            board.board[2][5],
+            Some((PieceType::Pawn, PieceColor::White))
+        );
+    }
+
+    #[test]
+    fn invalid_fen() {
+        assert_eq!(
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP w KQkq - 0 1").unwrap_err(),
+            FenError::WrongRankCount(7)
+        );
+        assert_eq!(
+            Board::from_fen("rnbqkbnr/pppppppp/9/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1")
+                .unwrap_err(),
+            FenError::InvalidRank("9".to_string())
+        );
+        assert_eq!(
+            Board::from_fen("rnbqkbnr/ppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap_err(),
+            FenError::InvalidRank("ppppppp".to_string())
+        );
+        assert_eq!(
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNX w KQkq - 0 1")
+                .unwrap_err(),
+            FenError::InvalidPiece('X')
+        );
+        assert_eq!(
+            Board::from_fen("rnbq1bnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQ - 0 1").unwrap_err(),
+            FenError::InvalidKingCount(PieceColor::Black)
+        );
+        assert_eq!(
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR x KQkq - 0 1")
+                .unwrap_err(),
+            FenError::InvalidSideToMove("x".to_string())
+        );
+        assert_eq!(
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBN1 w KQkq - 0 1")
+                .unwrap_err(),
+            FenError::InvalidCastling("KQkq".to_string())
+        );
+        assert_eq!(
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KKq - 0 1").unwrap_err(),
+            FenError::InvalidCastling("KKq".to_string())
+        );
+        assert_eq!(
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq e6 0 1")
+                .unwrap_err(),
+            FenError::InvalidEnPassant("e6".to_string())
+        );
+        assert_eq!(
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - x 1")
+                .unwrap_err(),
+            FenError::InvalidHalfmoveClock("x".to_string())
+        );
+        assert_eq!(
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 0")
+                .unwrap_err(),
+            FenError::InvalidFullmoveNumber("0".to_string())
+        );
+    }
+}
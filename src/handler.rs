@@ -9,22 +9,32 @@ pub fn handle_key_events(key_event: KeyEvent, app: &mut App) -> AppResult<()> {
             app.quit();
         }
         // Exit application on `Ctrl-C`
-        KeyCode::Char('c') | KeyCode::Char('C') => {
-            if key_event.modifiers == KeyModifiers::CONTROL {
-                app.quit();
-            }
+        KeyCode::Char('c') | KeyCode::Char('C') if key_event.modifiers == KeyModifiers::CONTROL => {
+            app.quit();
         }
+        // Any other key closes the error popup
+        _ if app.error.is_some() => app.error = None,
         // Counter handlers
         KeyCode::Right => app.board.cursor_right(),
         KeyCode::Left => app.board.cursor_left(),
         KeyCode::Up => app.board.cursor_up(),
         KeyCode::Down => app.board.cursor_down(),
-        KeyCode::Char(' ') => app.board.select_cell(),
+        KeyCode::Char(' ') => app.select_cell(),
         KeyCode::Char('h') => app.show_popup(),
+        KeyCode::Char('e') => app.toggle_evaluation(),
+        KeyCode::Char('i') => app.ask_hint(),
         KeyCode::Char('r') => app.restart(),
+        KeyCode::Char('f') => app.restart_chess960(),
+        KeyCode::Char('s') => app.save_pgn(),
+        KeyCode::Char('a') => app.switch_computer(),
+        KeyCode::Char('u') => app.undo(),
+        KeyCode::Char('U') => app.redo(),
+        KeyCode::Char('d') => {
+            app.board.claim_fifty_move_draw();
+        }
         KeyCode::Esc => app.board.unselect_cell(),
         // Other handlers you could add here.
         _ => {}
     }
     Ok(())
-}
\ No newline at end of file
+}
@@ -16,8 +16,44 @@ pub mod handler;
 // chess pieces structs
 pub mod pieces;
 
+// bitboard move generation
+pub mod bitboard;
+
 pub mod board;
 
+// typed moves stored in the history
+pub mod moves;
+
+// how a game goes on or ended
+pub mod status;
+
+// forsyth-edwards notation import and export
+pub mod fen;
+
+// standard algebraic notation
+pub mod san;
+
+// portable game notation
+pub mod pgn;
+
+// fischer random starting positions
+pub mod chess960;
+
+// move generation counts to check the rules
+pub mod perft;
+
+// zobrist keys of positions
+pub mod zobrist;
+
+// static evaluation of positions
+pub mod evaluation;
+
+// computer opponent
+pub mod engine;
+
+// universal chess interface engines
+pub mod uci;
+
 pub mod constants;
 
 pub mod utils;
\ No newline at end of file
@@ -2,23 +2,80 @@
 extern crate chess;
 
 use chess::app::{App, AppResult};
+use chess::board::Board;
 use chess::event::{Event, EventHandler};
 use chess::handler::handle_key_events;
+use chess::pieces::PieceColor;
 use chess::tui::Tui;
+use chess::uci::UciServer;
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
+use std::env;
 use std::io;
 
 fn main() -> AppResult<()> {
+    let args: Vec<String> = env::args().collect();
+
+    // count the moves from a position with `perft <fen> <depth>` instead of playing.
+    if args.get(1).is_some_and(|arg| arg == "perft") {
+        return perft(&args[2..]);
+    }
+
+    // play as a UCI engine on stdin and stdout with `--uci`, for chess interfaces and tournament managers.
+    if args.iter().any(|arg| arg == "--uci") {
+        UciServer::new(io::stdout()).run(io::stdin().lock());
+        return Ok(());
+    }
+
     // create an application.
     let mut app = App::new();
 
+    // load a game given with `--pgn <file>`.
+    if let Some(index) = args.iter().position(|arg| arg == "--pgn") {
+        match args.get(index + 1) {
+            Some(path) => app.load_pgn(path)?,
+            None => return Err("--pgn expects a file path".into()),
+        }
+    }
+
+    // start a Chess960 game with `--chess960 [position number]`, a random position by default.
+    if let Some(index) = args.iter().position(|arg| arg == "--chess960") {
+        match args.get(index + 1).filter(|arg| !arg.starts_with("--")) {
+            Some(number) => match number.parse::<u16>() {
+                Ok(number) => app.start_chess960(Some(number))?,
+                Err(_) => return Err("--chess960 expects a position number from 0 to 959".into()),
+            },
+            None => app.start_chess960(None)?,
+        }
+    }
+
+    // play against an external UCI engine with `--engine <path>`, it plays Black unless `--ai` says otherwise.
+    if let Some(index) = args.iter().position(|arg| arg == "--engine") {
+        match args.get(index + 1) {
+            Some(path) => app.start_uci_engine(path)?,
+            None => return Err("--engine expects the path of a UCI engine".into()),
+        }
+    }
+
+    // play against the computer with `--ai <white|black>`, the color the computer plays.
+    if let Some(index) = args.iter().position(|arg| arg == "--ai") {
+        match args
+            .get(index + 1)
+            .map(|color| color.to_lowercase())
+            .as_deref()
+        {
+            Some("white") => app.set_computer(Some(PieceColor::White)),
+            Some("black") => app.set_computer(Some(PieceColor::Black)),
+            _ => return Err("--ai expects the color the computer plays, white or black".into()),
+        }
+    }
+
     // initialize the terminal user interface.
     let backend = CrosstermBackend::new(io::stderr());
     let terminal = Terminal::new(backend)?;
     let events = EventHandler::new(250);
     let mut tui = Tui::new(terminal, events);
-    
+
     tui.init()?;
 
     // start the main loop.
@@ -37,4 +94,30 @@ fn main() -> AppResult<()> {
     // exit the user interface.
     tui.exit()?;
     Ok(())
-}
\ No newline at end of file
+}
+
+// Prints the perft count after each move then the total, the FEN can be quoted or not.
+fn perft(args: &[String]) -> AppResult<()> {
+    let usage = "usage: chess perft <fen> <depth>";
+    let (depth, fen) = args.split_last().ok_or(usage)?;
+    let depth: u32 = depth.parse().map_err(|_| usage)?;
+    let board = Board::from_fen(&fen.join(" "))?;
+
+    let mut divide: Vec<(String, u64)> = board
+        .perft_divide(depth)
+        .into_iter()
+        .map(|(piece_move, count)| (piece_move.to_string(), count))
+        .collect();
+    divide.sort();
+    for (piece_move, count) in &divide {
+        println!("{}: {}", piece_move, count);
+    }
+    let total: u64 = if depth == 0 {
+        1
+    } else {
+        divide.iter().map(|(_, count)| count).sum()
+    };
+    println!();
+    println!("Nodes searched: {}", total);
+    Ok(())
+}
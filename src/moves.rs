@@ -0,0 +1,408 @@
+use std::{error::Error, fmt};
+
+use crate::{
+    pieces::{PieceColor, PieceType},
+    status::GameStatus,
+    utils::{coordinates_to_notation, did_piece_already_move, notation_to_coordinates},
+};
+
+// A cell of the board, y is the row from the top (rank 8) and x the column from the left (file a)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Square {
+    pub y: i8,
+    pub x: i8,
+}
+
+impl Square {
+    pub fn new(y: i8, x: i8) -> Self {
+        Self { y, x }
+    }
+
+    pub fn coordinates(self) -> [i8; 2] {
+        [self.y, self.x]
+    }
+
+    pub fn from_notation(notation: &str) -> Option<Self> {
+        notation_to_coordinates(notation).map(Self::from)
+    }
+}
+
+impl From<[i8; 2]> for Square {
+    fn from(coordinates: [i8; 2]) -> Self {
+        Self::new(coordinates[0], coordinates[1])
+    }
+}
+
+impl From<[usize; 2]> for Square {
+    fn from(coordinates: [usize; 2]) -> Self {
+        Self::new(coordinates[0] as i8, coordinates[1] as i8)
+    }
+}
+
+impl fmt::Display for Square {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", coordinates_to_notation(self.coordinates()))
+    }
+}
+
+// Small castle is on the king side (h file rook), big castle on the queen side (a file rook)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CastlingSide {
+    King,
+    Queen,
+}
+
+impl CastlingSide {
+    // Column the rook of this side starts on in a standard game
+    pub fn rook_x(self) -> i8 {
+        match self {
+            CastlingSide::King => 7,
+            CastlingSide::Queen => 0,
+        }
+    }
+
+    // Columns the king and the rook end on, the same in a standard game and in Chess960
+    pub fn king_destination_x(self) -> i8 {
+        match self {
+            CastlingSide::King => 6,
+            CastlingSide::Queen => 2,
+        }
+    }
+
+    pub fn rook_destination_x(self) -> i8 {
+        match self {
+            CastlingSide::King => 5,
+            CastlingSide::Queen => 3,
+        }
+    }
+}
+
+// Column of the rook each player can still castle with on each side, lost once the king or the
+// rook moves or the rook is taken. The rooks start on a and h in a standard game but can start
+// anywhere on the home line in Chess960
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CastlingRights {
+    pub white_king_side: Option<i8>,
+    pub white_queen_side: Option<i8>,
+    pub black_king_side: Option<i8>,
+    pub black_queen_side: Option<i8>,
+}
+
+impl CastlingRights {
+    pub fn all() -> Self {
+        Self {
+            white_king_side: Some(CastlingSide::King.rook_x()),
+            white_queen_side: Some(CastlingSide::Queen.rook_x()),
+            black_king_side: Some(CastlingSide::King.rook_x()),
+            black_queen_side: Some(CastlingSide::Queen.rook_x()),
+        }
+    }
+
+    // Rights read from the placement: the king and the rook on their standard starting cells and
+    // not moved according to the history
+    pub fn from_board(
+        board: [[Option<(PieceType, PieceColor)>; 8]; 8],
+        move_history: &[Move],
+    ) -> Self {
+        let mut rights = Self::default();
+        for color in [PieceColor::White, PieceColor::Black] {
+            let line = home_line(color);
+            if board[line as usize][KING_X as usize] != Some((PieceType::King, color))
+                || did_piece_already_move(move_history, (PieceType::King, [line, KING_X]))
+            {
+                continue;
+            }
+            for side in [CastlingSide::King, CastlingSide::Queen] {
+                let rook = [line, side.rook_x()];
+                if board[line as usize][side.rook_x() as usize] == Some((PieceType::Rook, color))
+                    && !did_piece_already_move(move_history, (PieceType::Rook, rook))
+                {
+                    rights.set(color, side, Some(side.rook_x()));
+                }
+            }
+        }
+        rights
+    }
+
+    pub fn has(self, color: PieceColor, side: CastlingSide) -> bool {
+        self.rook_x(color, side).is_some()
+    }
+
+    pub fn rook_x(self, color: PieceColor, side: CastlingSide) -> Option<i8> {
+        match (color, side) {
+            (PieceColor::White, CastlingSide::King) => self.white_king_side,
+            (PieceColor::White, CastlingSide::Queen) => self.white_queen_side,
+            (PieceColor::Black, CastlingSide::King) => self.black_king_side,
+            (PieceColor::Black, CastlingSide::Queen) => self.black_queen_side,
+        }
+    }
+
+    pub fn set(&mut self, color: PieceColor, side: CastlingSide, rook_x: Option<i8>) {
+        let right = match (color, side) {
+            (PieceColor::White, CastlingSide::King) => &mut self.white_king_side,
+            (PieceColor::White, CastlingSide::Queen) => &mut self.white_queen_side,
+            (PieceColor::Black, CastlingSide::King) => &mut self.black_king_side,
+            (PieceColor::Black, CastlingSide::Queen) => &mut self.black_queen_side,
+        };
+        *right = rook_x;
+    }
+
+    // Drop the rights a move takes away: both rights of a king that moves, and the right of
+    // a rook leaving its cell or taken on it
+    pub fn update(&mut self, piece_move: &Move) {
+        if piece_move.piece_type == PieceType::King {
+            self.set(piece_move.piece_color, CastlingSide::King, None);
+            self.set(piece_move.piece_color, CastlingSide::Queen, None);
+        }
+        for square in [piece_move.from, piece_move.to] {
+            for color in [PieceColor::White, PieceColor::Black] {
+                for side in [CastlingSide::King, CastlingSide::Queen] {
+                    if square.y == home_line(color) && self.rook_x(color, side) == Some(square.x) {
+                        self.set(color, side, None);
+                    }
+                }
+            }
+        }
+    }
+}
+
+// Column the king starts on
+pub const KING_X: i8 = 4;
+
+// Row the pieces of a color start on
+pub fn home_line(color: PieceColor) -> i8 {
+    match color {
+        PieceColor::White => 7,
+        PieceColor::Black => 0,
+    }
+}
+
+// Pieces a pawn can be promoted to, in the order of the promotion popup
+pub const PROMOTION_PIECES: [PieceType; 4] = [
+    PieceType::Queen,
+    PieceType::Rook,
+    PieceType::Bishop,
+    PieceType::Knight,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoveKind {
+    Normal,
+    // A pawn moving two cells from its starting row, it can be taken en passant
+    DoublePawnPush,
+    EnPassant,
+    // The king moves on its rook, then the king and the rook are placed on the g and f files
+    // (king side) or the c and d files (queen side)
+    Castling(CastlingSide),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Move {
+    pub from: Square,
+    pub to: Square,
+    pub piece_type: PieceType,
+    pub piece_color: PieceColor,
+    pub captured: Option<PieceType>,
+    pub promotion: Option<PieceType>,
+    pub kind: MoveKind,
+}
+
+impl Move {
+    // Move of a piece without capture, double pawn pushes and standard castling are told apart
+    // from the distance travelled, the board tells Chess960 castling apart in build_move
+    pub fn new(piece_type: PieceType, piece_color: PieceColor, from: [i8; 2], to: [i8; 2]) -> Self {
+        let kind = match piece_type {
+            PieceType::Pawn if (from[0] - to[0]).abs() == 2 => MoveKind::DoublePawnPush,
+            PieceType::King if to[1] - from[1] > 1 => MoveKind::Castling(CastlingSide::King),
+            PieceType::King if from[1] - to[1] > 1 => MoveKind::Castling(CastlingSide::Queen),
+            _ => MoveKind::Normal,
+        };
+        Self {
+            from: Square::from(from),
+            to: Square::from(to),
+            piece_type,
+            piece_color,
+            captured: None,
+            promotion: None,
+            kind,
+        }
+    }
+
+    pub fn is_capture(&self) -> bool {
+        self.captured.is_some()
+    }
+
+    pub fn is_castling(&self) -> bool {
+        matches!(self.kind, MoveKind::Castling(_))
+    }
+
+    // Cell a double pawn push jumped over, where the pawn can be taken en passant
+    pub fn en_passant_target(&self) -> Option<Square> {
+        (self.kind == MoveKind::DoublePawnPush)
+            .then(|| Square::new((self.from.y + self.to.y) / 2, self.from.x))
+    }
+}
+
+// What a move played in place can't be taken back without, see Board::make_move
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MoveUndo {
+    pub captured: Option<(PieceType, PieceColor)>,
+    pub castling_rights: CastlingRights,
+    pub en_passant: Option<Square>,
+    pub halfmove_clock: u32,
+    pub zobrist_key: u64,
+}
+
+// Coordinate notation of the move, "e2e4" or "e7e8q" for a promotion
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.from, self.to)?;
+        if let Some(promotion) = self.promotion {
+            let letter = match promotion {
+                PieceType::Rook => "r",
+                PieceType::Bishop => "b",
+                PieceType::Knight => "n",
+                _ => "q",
+            };
+            write!(f, "{}", letter)?;
+        }
+        Ok(())
+    }
+}
+
+// What playing a move led to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MoveOutcome {
+    // The move as stored in the history, with its capture, promotion and kind
+    pub piece_move: Move,
+    // The player to play now is in check
+    pub is_check: bool,
+    pub status: GameStatus,
+}
+
+impl MoveOutcome {
+    pub fn is_capture(&self) -> bool {
+        self.piece_move.is_capture()
+    }
+
+    pub fn is_checkmate(&self) -> bool {
+        matches!(self.status, GameStatus::Checkmate { .. })
+    }
+
+    pub fn is_draw(&self) -> bool {
+        self.status.is_draw()
+    }
+}
+
+// Why a move can't be played
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IllegalMoveError {
+    // A cell is not on the board
+    OutOfBoard,
+    GameOver,
+    NoPiece,
+    WrongSideToMove,
+    // The piece doesn't move that way, even on an empty board
+    UnreachableCell,
+    // Another piece stands on the way or on the cell
+    BlockedPath,
+    // The piece shields its king from an attack
+    PinnedPiece,
+    // The king would be in check after the move
+    KingInCheck,
+    // A pawn reaches the last row without a promotion piece
+    MissingPromotion,
+    // A promotion piece for a move that doesn't promote, or a pawn or king promotion
+    InvalidPromotion,
+}
+
+impl fmt::Display for IllegalMoveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            IllegalMoveError::OutOfBoard => write!(f, "the cell is not on the board"),
+            IllegalMoveError::GameOver => write!(f, "the game is over"),
+            IllegalMoveError::NoPiece => write!(f, "there is no piece to move"),
+            IllegalMoveError::WrongSideToMove => write!(f, "it is not this player's turn"),
+            IllegalMoveError::UnreachableCell => write!(f, "the piece doesn't move that way"),
+            IllegalMoveError::BlockedPath => write!(f, "the path of the piece is blocked"),
+            IllegalMoveError::PinnedPiece => write!(f, "the piece is pinned to its king"),
+            IllegalMoveError::KingInCheck => write!(f, "the king would be in check"),
+            IllegalMoveError::MissingPromotion => write!(f, "missing promotion piece"),
+            IllegalMoveError::InvalidPromotion => write!(f, "invalid promotion piece"),
+        }
+    }
+}
+
+impl Error for IllegalMoveError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        moves::{CastlingRights, CastlingSide, Move, MoveKind, Square},
+        pieces::{PieceColor, PieceType},
+    };
+
+    #[test]
+    fn squares() {
+        assert_eq!(Square::from_notation("e1"), Some(Square::new(7, 4)));
+        assert_eq!(Square::from_notation("a8"), Some(Square::new(0, 0)));
+        assert_eq!(Square::from_notation("i1"), None);
+        assert_eq!(Square::new(4, 4).to_string(), "e4");
+        assert_eq!(Square::from([1usize, 7]).coordinates(), [1, 7]);
+    }
+
+    #[test]
+    fn move_kinds() {
+        let pawn_push = Move::new(PieceType::Pawn, PieceColor::White, [6, 4], [4, 4]);
+        assert_eq!(pawn_push.kind, MoveKind::DoublePawnPush);
+        assert_eq!(pawn_push.to_string(), "e2e4");
+
+        let small_castle = Move::new(PieceType::King, PieceColor::White, [7, 4], [7, 7]);
+        assert_eq!(small_castle.kind, MoveKind::Castling(CastlingSide::King));
+
+        let big_castle = Move::new(PieceType::King, PieceColor::Black, [0, 4], [0, 0]);
+        assert_eq!(big_castle.kind, MoveKind::Castling(CastlingSide::Queen));
+
+        let mut promotion = Move::new(PieceType::Pawn, PieceColor::Black, [6, 0], [7, 0]);
+        promotion.promotion = Some(PieceType::Knight);
+        assert_eq!(promotion.kind, MoveKind::Normal);
+        assert_eq!(promotion.to_string(), "a2a1n");
+    }
+
+    #[test]
+    fn castling_rights_update() {
+        let mut rights = CastlingRights::all();
+
+        // A rook leaving its corner only drops its side
+        rights.update(&Move::new(
+            PieceType::Rook,
+            PieceColor::White,
+            [7, 7],
+            [5, 7],
+        ));
+        assert!(!rights.has(PieceColor::White, CastlingSide::King));
+        assert!(rights.has(PieceColor::White, CastlingSide::Queen));
+
+        // Taking a rook on its corner drops the right of its owner
+        let mut capture = Move::new(PieceType::Bishop, PieceColor::White, [1, 1], [0, 0]);
+        capture.captured = Some(PieceType::Rook);
+        rights.update(&capture);
+        assert!(!rights.has(PieceColor::Black, CastlingSide::Queen));
+        assert!(rights.has(PieceColor::Black, CastlingSide::King));
+
+        // A king move drops both
+        rights.update(&Move::new(
+            PieceType::King,
+            PieceColor::Black,
+            [0, 4],
+            [1, 4],
+        ));
+        assert_eq!(
+            rights,
+            CastlingRights {
+                white_queen_side: Some(0),
+                ..CastlingRights::default()
+            }
+        );
+    }
+}
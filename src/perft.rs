@@ -0,0 +1,127 @@
+use crate::{board::Board, moves::Move};
+
+impl Board {
+    // Number of move sequences of the given length from the position, used to check the rules
+    // against counts known from other programs
+    pub fn perft(&self, depth: u32) -> u64 {
+        self.clone().perft_in_place(depth)
+    }
+
+    // Perft count after each legal move, to find which move the counts differ on
+    pub fn perft_divide(&self, depth: u32) -> Vec<(Move, u64)> {
+        if depth == 0 {
+            return vec![];
+        }
+        let mut board = self.clone();
+        board
+            .legal_moves()
+            .iter()
+            .map(|piece_move| {
+                let undo = board.make_move(piece_move);
+                let count = board.perft_in_place(depth - 1);
+                board.unmake_move(piece_move, undo);
+                (*piece_move, count)
+            })
+            .collect()
+    }
+
+    // The moves are played and taken back on the same board
+    fn perft_in_place(&mut self, depth: u32) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let legal_moves = self.legal_moves();
+        if depth == 1 {
+            return legal_moves.len() as u64;
+        }
+        let mut count = 0;
+        for piece_move in &legal_moves {
+            let undo = self.make_move(piece_move);
+            count += self.perft_in_place(depth - 1);
+            self.unmake_move(piece_move, undo);
+        }
+        count
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{board::Board, fen::STARTING_POSITION_FEN};
+
+    fn assert_perft(fen: &str, counts: &[u64]) {
+        let board = Board::from_fen(fen).unwrap();
+        for (depth, count) in counts.iter().enumerate() {
+            assert_eq!(
+                board.perft(depth as u32 + 1),
+                *count,
+                "{} at depth {}",
+                fen,
+                depth + 1
+            );
+        }
+    }
+
+    #[test]
+    fn starting_position() {
+        assert_perft(STARTING_POSITION_FEN, &[20, 400, 8902, 197281]);
+    }
+
+    #[test]
+    fn kiwipete() {
+        assert_perft(
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            &[48, 2039, 97862],
+        );
+    }
+
+    #[test]
+    fn en_passant_and_pins() {
+        assert_perft(
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            &[14, 191, 2812, 43238],
+        );
+    }
+
+    #[test]
+    fn castling_and_promotions() {
+        assert_perft(
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            &[6, 264, 9467, 422333],
+        );
+    }
+
+    #[test]
+    fn chess960_castling() {
+        assert_perft(
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+            &[21, 528, 12189, 326672],
+        );
+    }
+
+    #[test]
+    fn perft_divide() {
+        let board = Board::standard();
+        let divide = board.perft_divide(2);
+        assert_eq!(divide.len(), 20);
+        assert!(divide.iter().all(|(_, count)| *count == 20));
+        assert!(divide
+            .iter()
+            .any(|(piece_move, _)| piece_move.to_string() == "g1f3"));
+    }
+
+    #[test]
+    fn promotion_with_check() {
+        assert_perft(
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            &[44, 1486, 62379],
+        );
+    }
+
+    #[test]
+    fn middlegame() {
+        assert_perft(
+            "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+            &[46, 2079, 89890],
+        );
+    }
+}
@@ -0,0 +1,735 @@
+use std::{
+    error::Error,
+    fmt,
+    iter::Peekable,
+    str::Chars,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{
+    board::Board,
+    fen::{FenError, STARTING_POSITION_FEN},
+    pieces::PieceColor,
+    san::{apply_move, san_to_move, SanError},
+    status::GameStatus,
+};
+
+// Width PGN export lines are wrapped at
+const MAX_LINE_LENGTH: usize = 80;
+
+// The Seven Tag Roster, unknown values are written as "?"
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgnHeaders {
+    pub event: String,
+    pub site: String,
+    pub date: String,
+    pub round: String,
+    pub white: String,
+    pub black: String,
+}
+
+impl Default for PgnHeaders {
+    fn default() -> Self {
+        Self {
+            event: "Casual game".to_string(),
+            site: "?".to_string(),
+            date: today(),
+            round: "-".to_string(),
+            white: "?".to_string(),
+            black: "?".to_string(),
+        }
+    }
+}
+
+// Current UTC date in the PGN YYYY.MM.DD format
+pub fn today() -> String {
+    let seconds = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let (year, month, day) = civil_from_days((seconds / 86_400) as i64);
+    format!("{:04}.{:02}.{:02}", year, month, day)
+}
+
+// Gregorian date of a number of days since 1970-01-01
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * shifted_month + 2) / 5 + 1) as u32;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    } as u32;
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
+fn escape_tag_value(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+const RESULT_TOKENS: [&str; 4] = ["1-0", "0-1", "1/2-1/2", "*"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PgnError {
+    // The text doesn't follow the PGN grammar
+    Syntax {
+        game: usize,
+        message: String,
+    },
+    // The FEN tag of the game can't be read
+    InvalidFen {
+        game: usize,
+        error: FenError,
+    },
+    // A move of the game can't be played, ply starts at 1 from the starting position
+    IllegalMove {
+        game: usize,
+        ply: usize,
+        san: String,
+        error: SanError,
+    },
+}
+
+impl fmt::Display for PgnError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PgnError::Syntax { game, message } => write!(f, "game {}: {}", game, message),
+            PgnError::InvalidFen { game, error } => {
+                write!(f, "game {}: invalid FEN tag, {}", game, error)
+            }
+            PgnError::IllegalMove {
+                game,
+                ply,
+                san,
+                error,
+            } => write!(f, "game {}, ply {}: '{}' is an {}", game, ply, san, error),
+        }
+    }
+}
+
+impl Error for PgnError {}
+
+// A game read from a PGN file, replayed up to its last move
+#[derive(Debug)]
+pub struct PgnGame {
+    pub tags: Vec<(String, String)>,
+    pub board: Board,
+    pub result: String,
+}
+
+impl PgnGame {
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag_name, _)| tag_name == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    // The Seven Tag Roster of the game, to write it back with the same tags
+    pub fn headers(&self) -> PgnHeaders {
+        let tag = |name: &str| self.tag(name).unwrap_or("?").to_string();
+        PgnHeaders {
+            event: tag("Event"),
+            site: tag("Site"),
+            date: tag("Date"),
+            round: tag("Round"),
+            white: tag("White"),
+            black: tag("Black"),
+        }
+    }
+}
+
+// Tags and moves of a game before it is replayed
+#[derive(Debug, Default)]
+struct RawGame {
+    tags: Vec<(String, String)>,
+    moves: Vec<String>,
+    result: Option<String>,
+}
+
+impl RawGame {
+    fn is_empty(&self) -> bool {
+        self.tags.is_empty() && self.moves.is_empty() && self.result.is_none()
+    }
+}
+
+fn skip_until(chars: &mut Peekable<Chars>, end: char) {
+    for ch in chars.by_ref() {
+        if ch == end {
+            break;
+        }
+    }
+}
+
+fn parse_tag(chars: &mut Peekable<Chars>, game: usize) -> Result<(String, String), PgnError> {
+    let syntax_error = |message: &str| PgnError::Syntax {
+        game,
+        message: message.to_string(),
+    };
+
+    let mut name = String::new();
+    while let Some(&ch) = chars.peek() {
+        if ch.is_whitespace() || ch == '"' || ch == ']' {
+            break;
+        }
+        name.push(ch);
+        chars.next();
+    }
+    while chars.peek().is_some_and(|ch| ch.is_whitespace()) {
+        chars.next();
+    }
+    if name.is_empty() || chars.next() != Some('"') {
+        return Err(syntax_error("malformed tag pair"));
+    }
+
+    let mut value = String::new();
+    loop {
+        match chars.next() {
+            Some('\\') => match chars.next() {
+                Some(ch) => value.push(ch),
+                None => return Err(syntax_error("unterminated tag value")),
+            },
+            Some('"') => break,
+            Some(ch) => value.push(ch),
+            None => return Err(syntax_error("unterminated tag value")),
+        }
+    }
+
+    while chars.peek().is_some_and(|ch| ch.is_whitespace()) {
+        chars.next();
+    }
+    if chars.next() != Some(']') {
+        return Err(syntax_error(&format!("tag {} is not closed", name)));
+    }
+    Ok((name, value))
+}
+
+// Split the text in games, comments, NAGs and variations are skipped
+fn parse_raw_games(input: &str) -> Result<Vec<RawGame>, PgnError> {
+    let mut games: Vec<RawGame> = vec![];
+    let mut game = RawGame::default();
+    let mut variation_depth = 0;
+    let mut chars = input.chars().peekable();
+    let mut line_start = true;
+
+    while let Some(&ch) = chars.peek() {
+        let game_number = games.len() + 1;
+        let syntax_error = |message: &str| PgnError::Syntax {
+            game: game_number,
+            message: message.to_string(),
+        };
+
+        // Escape lines start with a % and are ignored
+        if ch == '%' && line_start {
+            skip_until(&mut chars, '\n');
+            continue;
+        }
+        line_start = ch == '\n';
+        if ch.is_whitespace() {
+            chars.next();
+            continue;
+        }
+
+        match ch {
+            '[' if variation_depth == 0 => {
+                chars.next();
+                // Tags after moves belong to the next game
+                if !game.moves.is_empty() || game.result.is_some() {
+                    games.push(std::mem::take(&mut game));
+                }
+                game.tags.push(parse_tag(&mut chars, games.len() + 1)?);
+            }
+            '{' => {
+                chars.next();
+                skip_until(&mut chars, '}');
+            }
+            ';' => {
+                skip_until(&mut chars, '\n');
+                line_start = true;
+            }
+            '(' => {
+                chars.next();
+                variation_depth += 1;
+            }
+            ')' => {
+                chars.next();
+                if variation_depth == 0 {
+                    return Err(syntax_error("unexpected ')'"));
+                }
+                variation_depth -= 1;
+            }
+            _ => {
+                let mut symbol = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_whitespace() || "[]{}();".contains(ch) {
+                        break;
+                    }
+                    symbol.push(ch);
+                    chars.next();
+                }
+                if symbol.is_empty() {
+                    return Err(syntax_error(&format!("unexpected '{}'", ch)));
+                }
+                if variation_depth > 0 || symbol.starts_with('$') {
+                    continue;
+                }
+                if RESULT_TOKENS.contains(&symbol.as_str()) {
+                    game.result = Some(symbol);
+                    games.push(std::mem::take(&mut game));
+                    continue;
+                }
+
+                // Move numbers are either alone ("12." or "12...") or glued to the move ("12.e4"),
+                // the digits of a castling written with zeros ("0-0") are not one
+                let after_digits = symbol.trim_start_matches(|ch: char| ch.is_ascii_digit());
+                if after_digits.is_empty() {
+                    return Err(syntax_error(&format!("unexpected '{}'", symbol)));
+                }
+                let san = if after_digits.len() < symbol.len() && after_digits.starts_with('.') {
+                    after_digits.trim_start_matches('.')
+                } else {
+                    symbol.as_str()
+                };
+                if !san.is_empty() {
+                    game.moves.push(san.to_string());
+                }
+            }
+        }
+    }
+
+    if variation_depth > 0 {
+        return Err(PgnError::Syntax {
+            game: games.len() + 1,
+            message: "unterminated variation".to_string(),
+        });
+    }
+    if !game.is_empty() {
+        games.push(game);
+    }
+    Ok(games)
+}
+
+// Read every game of a PGN text and replay its moves from its starting position
+pub fn parse_pgn(input: &str) -> Result<Vec<PgnGame>, PgnError> {
+    let mut games: Vec<PgnGame> = vec![];
+
+    for (index, raw_game) in parse_raw_games(input)?.into_iter().enumerate() {
+        let game = index + 1;
+        let fen = raw_game
+            .tags
+            .iter()
+            .find(|(name, _)| name == "FEN")
+            .map(|(_, value)| value.as_str());
+        let mut board = match fen {
+            Some(fen) => {
+                Board::from_fen(fen).map_err(|error| PgnError::InvalidFen { game, error })?
+            }
+            None => Board::standard(),
+        };
+        // The Variant tag tells a Chess960 game apart even from the standard starting position
+        board.starting_position.chess960 |= raw_game.tags.iter().any(|(name, value)| {
+            name == "Variant"
+                && ["chess960", "chess 960", "fischerandom"]
+                    .contains(&value.to_lowercase().as_str())
+        });
+
+        for (index, san) in raw_game.moves.iter().enumerate() {
+            let (from, to, promotion) =
+                san_to_move(&board, san).map_err(|error| PgnError::IllegalMove {
+                    game,
+                    ply: index + 1,
+                    san: san.to_string(),
+                    error,
+                })?;
+            apply_move(&mut board, from, to, promotion);
+        }
+
+        // A result the position doesn't explain was decided away from the board
+        let result = raw_game.result.unwrap_or("*".to_string());
+        if !board.is_game_over() {
+            let on_time = raw_game.tags.iter().any(|(name, value)| {
+                name == "Termination" && value.eq_ignore_ascii_case("time forfeit")
+            });
+            board.ending = match (result.as_str(), on_time) {
+                ("1-0", true) => Some(GameStatus::Timeout {
+                    winner: PieceColor::White,
+                }),
+                ("0-1", true) => Some(GameStatus::Timeout {
+                    winner: PieceColor::Black,
+                }),
+                ("1-0", false) => Some(GameStatus::Resignation {
+                    winner: PieceColor::White,
+                }),
+                ("0-1", false) => Some(GameStatus::Resignation {
+                    winner: PieceColor::Black,
+                }),
+                ("1/2-1/2", _) if board.can_claim_fifty_move_draw() => Some(GameStatus::FiftyMove),
+                ("1/2-1/2", _) => Some(GameStatus::Agreement),
+                _ => None,
+            };
+        }
+
+        games.push(PgnGame {
+            tags: raw_game.tags,
+            board,
+            result,
+        });
+    }
+    Ok(games)
+}
+
+impl Board {
+    // PGN result token of the game, "*" while it is still going on
+    pub fn result_token(&self) -> &'static str {
+        self.status().result_token()
+    }
+
+    pub fn to_pgn(&self) -> String {
+        self.to_pgn_with_headers(&PgnHeaders::default())
+    }
+
+    // Export the game in Portable Game Notation with the given tags
+    pub fn to_pgn_with_headers(&self, headers: &PgnHeaders) -> String {
+        let result = self.result_token();
+        let mut pgn = String::new();
+
+        for (name, value) in [
+            ("Event", &headers.event),
+            ("Site", &headers.site),
+            ("Date", &headers.date),
+            ("Round", &headers.round),
+            ("White", &headers.white),
+            ("Black", &headers.black),
+        ] {
+            pgn += &format!("[{} \"{}\"]\n", name, escape_tag_value(value));
+        }
+        pgn += &format!("[Result \"{}\"]\n", result);
+
+        // Chess960 games always give their starting position
+        if self.is_chess960() {
+            pgn += "[Variant \"Chess960\"]\n";
+        }
+        let starting_fen = self.starting_board().to_fen();
+        if starting_fen != STARTING_POSITION_FEN || self.is_chess960() {
+            pgn += "[SetUp \"1\"]\n";
+            pgn += &format!("[FEN \"{}\"]\n", starting_fen);
+        }
+        pgn += "\n";
+
+        // Movetext tokens, the move number is repeated when black starts
+        let mut tokens: Vec<String> = vec![];
+        let mut fullmove_number = self.starting_position.fullmove_number;
+        let mut color = self.starting_position.player_turn;
+        for (ply, san) in self.moves_san().into_iter().enumerate() {
+            match color {
+                PieceColor::White => {
+                    tokens.push(format!("{}.", fullmove_number));
+                }
+                PieceColor::Black => {
+                    if ply == 0 {
+                        tokens.push(format!("{}...", fullmove_number));
+                    }
+                    fullmove_number += 1;
+                }
+            }
+            tokens.push(san);
+            color = match color {
+                PieceColor::White => PieceColor::Black,
+                PieceColor::Black => PieceColor::White,
+            };
+        }
+        tokens.push(result.to_string());
+
+        let mut line = String::new();
+        for token in tokens {
+            if !line.is_empty() && line.len() + 1 + token.len() > MAX_LINE_LENGTH {
+                pgn += &line;
+                pgn += "\n";
+                line.clear();
+            }
+            if !line.is_empty() {
+                line += " ";
+            }
+            line += &token;
+        }
+        pgn += &line;
+        pgn += "\n";
+
+        pgn
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        board::Board,
+        pgn::{civil_from_days, parse_pgn, PgnError, PgnHeaders},
+        pieces::{PieceColor, PieceType},
+        san::{apply_move, SanError},
+        status::GameStatus,
+    };
+
+    fn headers() -> PgnHeaders {
+        PgnHeaders {
+            date: "2024.01.02".to_string(),
+            ..PgnHeaders::default()
+        }
+    }
+
+    #[test]
+    fn dates() {
+        assert_eq!(civil_from_days(0), (1970, 1, 1));
+        assert_eq!(civil_from_days(19_723), (2024, 1, 1));
+        assert_eq!(civil_from_days(19_782), (2024, 2, 29));
+    }
+
+    #[test]
+    fn empty_game() {
+        assert_eq!(
+            Board::standard().to_pgn_with_headers(&headers()),
+            "[Event \"Casual game\"]\n\
+             [Site \"?\"]\n\
+             [Date \"2024.01.02\"]\n\
+             [Round \"-\"]\n\
+             [White \"?\"]\n\
+             [Black \"?\"]\n\
+             [Result \"*\"]\n\
+             \n\
+             *\n"
+        );
+    }
+
+    #[test]
+    fn finished_game() {
+        // Fool's mate
+        let mut board = Board::standard();
+        for (from, to) in [
+            ([6, 5], [5, 5]),
+            ([1, 4], [3, 4]),
+            ([6, 6], [4, 6]),
+            ([0, 3], [4, 7]),
+        ] {
+            apply_move(&mut board, from, to, None);
+        }
+        let pgn = board.to_pgn_with_headers(&headers());
+        assert!(pgn.contains("[Result \"0-1\"]\n"));
+        assert!(pgn.ends_with("\n1. f3 e5 2. g4 Qh4# 0-1\n"));
+    }
+
+    #[test]
+    fn game_from_a_position() {
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 b Q - 3 20").unwrap();
+        apply_move(&mut board, [0, 4], [0, 3], None);
+        apply_move(&mut board, [7, 4], [7, 0], None);
+
+        let pgn = board.to_pgn_with_headers(&headers());
+        assert!(pgn.contains("[SetUp \"1\"]\n[FEN \"4k3/8/8/8/8/8/8/R3K3 b Q - 3 20\"]\n"));
+        assert!(pgn.ends_with("\n20... Kd8 21. O-O-O+ *\n"));
+    }
+
+    #[test]
+    fn long_games_are_wrapped() {
+        let mut board = Board::standard();
+        for _ in 0..10 {
+            for (from, to) in [
+                ([7, 6], [5, 5]),
+                ([0, 6], [2, 5]),
+                ([5, 5], [7, 6]),
+                ([2, 5], [0, 6]),
+            ] {
+                apply_move(&mut board, from, to, None);
+            }
+        }
+
+        let pgn = board.to_pgn_with_headers(&headers());
+        let movetext = pgn.split("\n\n").nth(1).unwrap();
+        assert!(movetext.lines().count() > 1);
+        assert!(movetext.lines().all(|line| line.len() <= 80));
+        assert!(movetext.starts_with("1. Nf3 Nf6 2. Ng1 Ng8 3. Nf3"));
+    }
+
+    #[test]
+    fn parse_games() {
+        let pgn = r#"[Event "F/S Return Match"]
+[Site "Belgrade, Serbia JUG"]
+[Date "1992.11.04"]
+[Round "29"]
+[White "Fischer, Robert J."]
+[Black "Spassky, Boris V."]
+[Result "1/2-1/2"]
+
+1. e4 e5 2. Nf3 Nc6 3. Bb5 {This opening is called the Ruy Lopez.} 3... a6
+4. Ba4 Nf6 5. O-O Be7 6. Re1 b5 7. Bb3 d6 8. c3 O-O 9. h3 Nb8 10. d4 Nbd7
+11. c4 c6 12. cxb5 axb5 13. Nc3 Bb7 14. Bg5 b4 15. Nb1 h6 16. Bh4 c5 17. dxe5
+Nxe4 18. Bxe7 Qxe7 19. exd6 Qf6 20. Nbd2 Nxd6 21. Nc4 Nxc4 22. Bxc4 Nb6
+23. Ne5 Rae8 24. Bxf7+ Rxf7 25. Nxf7 Rxe1+ 26. Qxe1 Kxf7 27. Qe3 Qg5 28. Qxg5
+hxg5 29. b3 Ke6 30. a3 Kd6 31. axb4 cxb4 32. Ra5 Nd5 33. f3 Bc8 34. Kf2 Bf5
+35. Ra7 g6 36. Ra6+ Kc5 37. Ke1 Nf4 38. g3 Nxh3 39. Kd2 Kb5 40. Rd6 Kc5 41. Ra6
+Nf2 42. g4 Bd3 43. Re6 1/2-1/2
+
+[Event "Second"]
+[Result "1-0"]
+
+1.e4 $1 e5 (1... c5 2. Nf3 (2. c3) d6) 2.Qh5?! Nc6 ; a comment
+3.Bc4 Nf6?? 4.Qxf7# 1-0
+"#;
+        let games = parse_pgn(pgn).unwrap();
+        assert_eq!(games.len(), 2);
+
+        assert_eq!(games[0].tag("White"), Some("Fischer, Robert J."));
+        assert_eq!(games[0].headers().round, "29");
+        assert_eq!(games[0].result, "1/2-1/2");
+        assert_eq!(games[0].board.status(), GameStatus::Agreement);
+        assert_eq!(games[0].board.moves_history.len(), 85);
+        assert_eq!(
+            games[0].board.to_fen(),
+            "8/8/4R1p1/2k3p1/1p4P1/1P1b1P2/3K1n2/8 b - - 2 43"
+        );
+
+        assert_eq!(games[1].tag("Event"), Some("Second"));
+        assert_eq!(games[1].result, "1-0");
+        assert!(games[1].board.is_checkmate());
+        assert_eq!(games[1].board.result_token(), "1-0");
+        assert_eq!(
+            games[1].board.moves_san(),
+            vec!["e4", "e5", "Qh5", "Nc6", "Bc4", "Nf6", "Qxf7#"]
+        );
+    }
+
+    #[test]
+    fn parse_game_from_a_position() {
+        let pgn = "[FEN \"3r3k/4P3/8/8/8/8/8/4K3 w - - 0 1\"]\n[SetUp \"1\"]\n\n1. exd8=N *";
+        let games = parse_pgn(pgn).unwrap();
+
+        assert_eq!(games.len(), 1);
+        assert_eq!(games[0].result, "*");
+        assert_eq!(
+            games[0].board.board[0][3],
+            Some((PieceType::Knight, PieceColor::White))
+        );
+        assert_eq!(games[0].board.player_turn, PieceColor::Black);
+    }
+
+    #[test]
+    fn export_then_import() {
+        let mut board = Board::standard();
+        for (from, to) in [
+            ([6, 4], [4, 4]),
+            ([1, 3], [3, 3]),
+            ([4, 4], [3, 3]),
+            ([0, 3], [3, 3]),
+            ([7, 1], [5, 2]),
+        ] {
+            apply_move(&mut board, from, to, None);
+        }
+
+        let games = parse_pgn(&board.to_pgn()).unwrap();
+        assert_eq!(games[0].board.to_fen(), board.to_fen());
+        assert_eq!(games[0].board.moves_san(), board.moves_san());
+    }
+
+    #[test]
+    fn chess960_export_then_import() {
+        let mut board = Board::chess960(746).unwrap();
+        for (from, to) in [
+            ([7, 2], [5, 1]),
+            ([0, 2], [2, 1]),
+            ([7, 3], [5, 2]),
+            ([0, 3], [2, 2]),
+            ([7, 1], [7, 0]),
+        ] {
+            apply_move(&mut board, from, to, None);
+        }
+
+        let pgn = board.to_pgn();
+        assert!(pgn.contains("[Variant \"Chess960\"]\n[SetUp \"1\"]\n"));
+        assert!(
+            pgn.contains("[FEN \"rknnbbqr/pppppppp/8/8/8/8/PPPPPPPP/RKNNBBQR w KQkq - 0 1\"]\n")
+        );
+        assert!(pgn.contains("1. Nb3 Nb6 2. Nc3 Nc6 3. O-O-O *"));
+
+        let games = parse_pgn(&pgn).unwrap();
+        assert!(games[0].board.is_chess960());
+        assert_eq!(games[0].board.to_fen(), board.to_fen());
+
+        // Without the Variant tag the castling rights of the FEN tell it is Chess960
+        let pgn = "[FEN \"rknnbbqr/pppppppp/8/8/8/8/PPPPPPPP/RKNNBBQR w HAha - 0 1\"]\n\n1. Nb3 *";
+        assert!(parse_pgn(pgn).unwrap()[0].board.is_chess960());
+    }
+
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            parse_pgn("[Event \"a\"]\n\n1. e4 e5 2. Ke3 *").unwrap_err(),
+            PgnError::IllegalMove {
+                game: 1,
+                ply: 3,
+                san: "Ke3".to_string(),
+                error: SanError::Illegal
+            }
+        );
+        assert_eq!(
+            parse_pgn("1. e4 *\n\n[Event \"b\"]\n1. e4 e5 2. Nf3 Nc6 3. Ng5 Nd4 4. Ne6 Kd7 *")
+                .unwrap_err(),
+            PgnError::IllegalMove {
+                game: 2,
+                ply: 8,
+                san: "Kd7".to_string(),
+                error: SanError::Illegal
+            }
+        );
+        assert_eq!(
+            parse_pgn("[FEN \"4k3/8/8/8/8/8/8/4K2R w K - 0 1\"]\n1. Rh2 Kd8 2. R8 *").unwrap_err(),
+            PgnError::IllegalMove {
+                game: 1,
+                ply: 3,
+                san: "R8".to_string(),
+                error: SanError::Invalid
+            }
+        );
+        assert!(matches!(
+            parse_pgn("[FEN \"8/8/8/8/8/8/8/8 w - - 0 1\"]\n*").unwrap_err(),
+            PgnError::InvalidFen { game: 1, .. }
+        ));
+        assert!(matches!(
+            parse_pgn("[Event \"unterminated]\n1. e4 *").unwrap_err(),
+            PgnError::Syntax { game: 1, .. }
+        ));
+        assert!(matches!(
+            parse_pgn("1. e4 (1. d4 *").unwrap_err(),
+            PgnError::Syntax { game: 1, .. }
+        ));
+        assert_eq!(
+            parse_pgn("1. e4 e5 *").unwrap()[0].board.to_fen(),
+            "rnbqkbnr/pppp1ppp/8/4p3/4P3/8/PPPP1PPP/RNBQKBNR w KQkq e6 0 2"
+        );
+        assert!(matches!(
+            parse_pgn("1. e4 12 e5 *").unwrap_err(),
+            PgnError::Syntax { game: 1, .. }
+        ));
+    }
+
+    #[test]
+    fn castling_written_with_zeros() {
+        let games =
+            parse_pgn("1. e4 e5 2. Nf3 Nc6 3. Bc4 Bc5 4.0-0 Nf6 5. d3 d6 6. Nc3 Bg4 7. h3 0-0 *")
+                .unwrap();
+        let board = &games[0].board;
+        assert_eq!(board.moves_san()[6], "O-O");
+        assert_eq!(board.moves_san()[13], "O-O");
+        assert_eq!(
+            board.board[7][5..7],
+            [
+                Some((PieceType::Rook, PieceColor::White)),
+                Some((PieceType::King, PieceColor::White))
+            ]
+        );
+    }
+}
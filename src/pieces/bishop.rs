@@ -1,7 +1,7 @@
 use super::{Movable, PieceColor, PieceType, Position};
+use crate::moves::{CastlingRights, Move};
 use crate::utils::{
-    cleaned_positions, get_piece_color, impossible_positions_king_checked, is_cell_color_ally,
-    is_piece_opposite_king, is_valid,
+    cleaned_positions, get_piece_color, is_cell_color_ally, is_piece_opposite_king, is_valid,
 };
 pub struct Bishop;
 
@@ -11,7 +11,7 @@ impl Movable for Bishop {
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
         allow_move_on_ally_positions: bool,
-        _move_history: Vec<(Option<PieceType>, String)>,
+        _move_history: &[Move],
     ) -> Vec<Vec<i8>> {
         let mut positions: Vec<Vec<i8>> = vec![];
 
@@ -167,23 +167,23 @@ impl Position for Bishop {
         coordinates: [i8; 2],
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
-        move_history: Vec<(Option<PieceType>, String)>,
-        _is_king_checked: bool,
+        move_history: &[Move],
+        castling_rights: CastlingRights,
     ) -> Vec<Vec<i8>> {
-        // if the king is checked we clean all the position not resolving the check
-        impossible_positions_king_checked(
+        PieceType::Bishop.authorized_positions(
             coordinates,
-            Self::piece_move(coordinates, color, board, false, move_history.clone()),
-            board,
             color,
+            board,
             move_history,
+            castling_rights,
         )
     }
+
     fn protected_positions(
         coordinates: [i8; 2],
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
-        move_history: Vec<(Option<PieceType>, String)>,
+        move_history: &[Move],
     ) -> Vec<Vec<i8>> {
         Self::piece_move(coordinates, color, board, true, move_history)
     }
@@ -205,8 +205,8 @@ impl Bishop {
 mod tests {
     use crate::{
         board::Board,
+        moves::CastlingRights,
         pieces::{bishop::Bishop, PieceColor, PieceType, Position},
-        utils::is_getting_checked,
     };
 
     #[test]
@@ -250,8 +250,13 @@ mod tests {
         ];
         right_positions.sort();
 
-        let mut positions =
-            Bishop::authorized_positions([4, 4], PieceColor::White, board.board, vec![], false);
+        let mut positions = Bishop::authorized_positions(
+            [4, 4],
+            PieceColor::White,
+            board.board,
+            &[],
+            CastlingRights::default(),
+        );
         positions.sort();
 
         assert_eq!(right_positions, positions);
@@ -305,8 +310,13 @@ mod tests {
         ];
         right_positions.sort();
 
-        let mut positions =
-            Bishop::authorized_positions([4, 4], PieceColor::White, board.board, vec![], false);
+        let mut positions = Bishop::authorized_positions(
+            [4, 4],
+            PieceColor::White,
+            board.board,
+            &[],
+            CastlingRights::default(),
+        );
         positions.sort();
 
         assert_eq!(right_positions, positions);
@@ -374,8 +384,13 @@ mod tests {
         ];
         right_positions.sort();
 
-        let mut positions =
-            Bishop::authorized_positions([4, 4], PieceColor::White, board.board, vec![], false);
+        let mut positions = Bishop::authorized_positions(
+            [4, 4],
+            PieceColor::White,
+            board.board,
+            &[],
+            CastlingRights::default(),
+        );
         positions.sort();
 
         assert_eq!(right_positions, positions);
@@ -423,9 +438,6 @@ mod tests {
         let mut board = Board::new(custom_board, PieceColor::Black, vec![]);
         board.set_board(custom_board);
 
-        let is_king_checked =
-            is_getting_checked(board.board, board.player_turn, board.moves_history);
-
         let mut right_positions = vec![vec![4, 4]];
         right_positions.sort();
 
@@ -433,8 +445,8 @@ mod tests {
             [5, 5],
             PieceColor::Black,
             board.board,
-            vec![],
-            is_king_checked,
+            &[],
+            CastlingRights::default(),
         );
         positions.sort();
 
@@ -483,9 +495,6 @@ mod tests {
         let mut board = Board::new(custom_board, PieceColor::Black, vec![]);
         board.set_board(custom_board);
 
-        let is_king_checked =
-            is_getting_checked(board.board, board.player_turn, board.moves_history);
-
         let mut right_positions: Vec<Vec<i8>> = vec![];
         right_positions.sort();
 
@@ -493,8 +502,8 @@ mod tests {
             [5, 6],
             PieceColor::Black,
             board.board,
-            vec![],
-            is_king_checked,
+            &[],
+            CastlingRights::default(),
         );
         positions.sort();
 
@@ -543,9 +552,6 @@ mod tests {
         let mut board = Board::new(custom_board, PieceColor::Black, vec![]);
         board.set_board(custom_board);
 
-        let is_king_checked =
-            is_getting_checked(board.board, board.player_turn, board.moves_history);
-
         let mut right_positions: Vec<Vec<i8>> = vec![vec![2, 6], vec![3, 7]];
         right_positions.sort();
 
@@ -553,11 +559,11 @@ mod tests {
             [1, 5],
             PieceColor::Black,
             board.board,
-            vec![],
-            is_king_checked,
+            &[],
+            CastlingRights::default(),
         );
         positions.sort();
 
         assert_eq!(right_positions, positions);
     }
-}
\ No newline at end of file
+}
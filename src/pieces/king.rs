@@ -1,8 +1,7 @@
 use super::{Movable, PieceColor, PieceType, Position};
-use crate::utils::{
-    cleaned_positions, did_piece_already_move, get_all_protected_cells, get_piece_type,
-    is_cell_color_ally, is_valid, is_vec_in_array,
-};
+use crate::bitboard::{square_index, Bitboards};
+use crate::moves::{home_line, CastlingRights, CastlingSide, Move};
+use crate::utils::{cleaned_positions, is_cell_color_ally, is_valid};
 
 pub struct King;
 
@@ -12,7 +11,7 @@ impl Movable for King {
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
         allow_move_on_ally_positions: bool,
-        _move_history: Vec<(Option<PieceType>, String)>,
+        _move_history: &[Move],
     ) -> Vec<Vec<i8>> {
         let mut positions: Vec<Vec<i8>> = vec![];
         let y = coordinates[0];
@@ -45,51 +44,16 @@ impl Position for King {
         coordinates: [i8; 2],
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
-        move_history: Vec<(Option<PieceType>, String)>,
-        is_king_checked: bool,
+        move_history: &[Move],
+        castling_rights: CastlingRights,
     ) -> Vec<Vec<i8>> {
-        let mut positions: Vec<Vec<i8>> = vec![];
-        let checked_cells = get_all_protected_cells(board, color, move_history.clone());
-
-        let rook_big_castle_x = 0;
-        let rook_small_castle_x = 7;
-        let king_x = 4;
-        let king_line = if color == PieceColor::White { 7 } else { 0 };
-
-        // We check the condition for small and big castling
-        if !did_piece_already_move(&move_history, (Some(PieceType::King), [king_line, king_x]))
-            && !is_king_checked
-        {
-            // We check if there is no pieces between tower and king
-
-            // Big castle check
-            if !did_piece_already_move(
-                &move_history,
-                (Some(PieceType::Rook), [king_line, rook_big_castle_x]),
-            ) && King::check_castling_condition(board, color, 0, 3, &checked_cells)
-            {
-                positions.push(vec![king_line, 0]);
-            }
-            // Small castle check
-            if !did_piece_already_move(
-                &move_history,
-                (Some(PieceType::Rook), [king_line, rook_small_castle_x]),
-            ) && King::check_castling_condition(board, color, 5, 7, &checked_cells)
-            {
-                positions.push(vec![king_line, 7]);
-            }
-        }
-
-        // Here we only want king positions that are not in impossible (already checked)
-        let king_cells = King::piece_move(coordinates, color, board, false, move_history);
-
-        for king_position in king_cells.clone() {
-            if !is_vec_in_array(checked_cells.clone(), [king_position[0], king_position[1]]) {
-                positions.push(king_position);
-            }
-        }
-
-        positions
+        PieceType::King.authorized_positions(
+            coordinates,
+            color,
+            board,
+            move_history,
+            castling_rights,
+        )
     }
 
     // This method is used to calculated the cells the king is actually covering and is used when the other king authorized position is called
@@ -97,7 +61,7 @@ impl Position for King {
         coordinates: [i8; 2],
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
-        move_history: Vec<(Option<PieceType>, String)>,
+        move_history: &[Move],
     ) -> Vec<Vec<i8>> {
         Self::piece_move(coordinates, color, board, true, move_history)
     }
@@ -114,34 +78,22 @@ impl King {
     "
     }
 
+    // The rook must be in place, the cells the king and the rook go through must be empty but for
+    // themselves and the cells the king goes through must not be attacked once both left their
+    // cells. The king and the rook end on the same cells in a standard game and in Chess960
     pub fn check_castling_condition(
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
+        coordinates: [i8; 2],
         color: PieceColor,
-        start: i8,
-        end: i8,
-        checked_cells: &Vec<Vec<i8>>,
+        rook_x: i8,
+        side: CastlingSide,
     ) -> bool {
-        let king_line = if color == PieceColor::White { 7 } else { 0 };
-
-        let mut valid_for_castling = true;
-
-        for i in start..=end {
-            let new_coordinates = [king_line, i];
-
-            if is_vec_in_array(checked_cells.clone(), new_coordinates) {
-                valid_for_castling = false;
-            }
-            if (i == 7 || i == 0)
-                && (get_piece_type(board, new_coordinates) != Some(PieceType::Rook)
-                    || !is_cell_color_ally(board, new_coordinates, color))
-            {
-                valid_for_castling = false;
-            } else if i != 7 && i != 0 && get_piece_type(board, new_coordinates).is_some() {
-                valid_for_castling = false;
-            }
-        }
-
-        valid_for_castling
+        Bitboards::from_board(&board).can_castle(
+            color,
+            square_index(coordinates),
+            square_index([home_line(color), rook_x]),
+            side,
+        )
     }
 }
 
@@ -149,8 +101,8 @@ impl King {
 mod tests {
     use crate::{
         board::Board,
+        moves::{CastlingRights, Move},
         pieces::{king::King, PieceColor, PieceType, Position},
-        utils::is_getting_checked,
     };
 
     #[test]
@@ -207,8 +159,13 @@ mod tests {
         let mut right_positions = vec![vec![4, 5], vec![5, 4]];
         right_positions.sort();
 
-        let mut positions =
-            King::authorized_positions([4, 4], PieceColor::White, board.board, vec![], false);
+        let mut positions = King::authorized_positions(
+            [4, 4],
+            PieceColor::White,
+            board.board,
+            &[],
+            CastlingRights::default(),
+        );
         positions.sort();
 
         assert_eq!(right_positions, positions);
@@ -268,8 +225,13 @@ mod tests {
         let mut right_positions = vec![vec![3, 4]];
         right_positions.sort();
 
-        let mut positions =
-            King::authorized_positions([4, 4], PieceColor::White, board.board, vec![], false);
+        let mut positions = King::authorized_positions(
+            [4, 4],
+            PieceColor::White,
+            board.board,
+            &[],
+            CastlingRights::default(),
+        );
         positions.sort();
 
         assert_eq!(right_positions, positions);
@@ -329,8 +291,13 @@ mod tests {
         let mut right_positions = vec![vec![4, 5]];
         right_positions.sort();
 
-        let mut positions =
-            King::authorized_positions([4, 4], PieceColor::White, board.board, vec![], false);
+        let mut positions = King::authorized_positions(
+            [4, 4],
+            PieceColor::White,
+            board.board,
+            &[],
+            CastlingRights::default(),
+        );
         positions.sort();
 
         assert_eq!(right_positions, positions);
@@ -390,8 +357,13 @@ mod tests {
         let mut right_positions = vec![vec![7, 3], vec![7, 0]];
         right_positions.sort();
 
-        let mut positions =
-            King::authorized_positions([7, 4], PieceColor::White, board.board, vec![], false);
+        let mut positions = King::authorized_positions(
+            [7, 4],
+            PieceColor::White,
+            board.board,
+            &[],
+            CastlingRights::all(),
+        );
         positions.sort();
 
         assert_eq!(right_positions, positions);
@@ -451,8 +423,13 @@ mod tests {
         let mut right_positions = vec![vec![0, 5], vec![0, 7]];
         right_positions.sort();
 
-        let mut positions =
-            King::authorized_positions([0, 4], PieceColor::Black, board.board, vec![], false);
+        let mut positions = King::authorized_positions(
+            [0, 4],
+            PieceColor::Black,
+            board.board,
+            &[],
+            CastlingRights::all(),
+        );
         positions.sort();
 
         assert_eq!(right_positions, positions);
@@ -512,8 +489,13 @@ mod tests {
         let mut right_positions = vec![vec![0, 5]];
         right_positions.sort();
 
-        let mut positions =
-            King::authorized_positions([0, 4], PieceColor::Black, board.board, vec![], false);
+        let mut positions = King::authorized_positions(
+            [0, 4],
+            PieceColor::Black,
+            board.board,
+            &[],
+            CastlingRights::all(),
+        );
         positions.sort();
 
         assert_eq!(right_positions, positions);
@@ -570,9 +552,6 @@ mod tests {
         let mut board = Board::new(custom_board, PieceColor::Black, vec![]);
         board.set_board(custom_board);
 
-        let is_king_checked =
-            is_getting_checked(board.board, board.player_turn, board.moves_history);
-
         let mut right_positions = vec![vec![0, 5]];
         right_positions.sort();
 
@@ -580,8 +559,8 @@ mod tests {
             [0, 4],
             PieceColor::Black,
             board.board,
-            vec![],
-            is_king_checked,
+            &[],
+            CastlingRights::all(),
         );
         positions.sort();
 
@@ -639,6 +618,16 @@ mod tests {
         let mut board = Board::default();
         board.set_board(custom_board);
 
+        let history = [
+            Move::new(PieceType::Rook, PieceColor::Black, [0, 7], [4, 7]),
+            Move::new(PieceType::Pawn, PieceColor::White, [6, 2], [5, 2]),
+            Move::new(PieceType::Rook, PieceColor::Black, [4, 7], [0, 7]),
+        ];
+        let mut castling_rights = CastlingRights::all();
+        for piece_move in &history {
+            castling_rights.update(piece_move);
+        }
+
         let mut right_positions = vec![vec![0, 5]];
         right_positions.sort();
 
@@ -646,15 +635,11 @@ mod tests {
             [0, 4],
             PieceColor::Black,
             board.board,
-            vec![
-                (Some(PieceType::Rook), "0747".to_string()),
-                (Some(PieceType::Pawn), "6252".to_string()),
-                (Some(PieceType::Rook), "4707".to_string()),
-            ],
-            false,
+            &history,
+            castling_rights,
         );
         positions.sort();
 
         assert_eq!(right_positions, positions);
     }
-}
\ No newline at end of file
+}
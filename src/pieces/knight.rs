@@ -1,7 +1,6 @@
 use super::{Movable, PieceColor, PieceType, Position};
-use crate::utils::{
-    cleaned_positions, impossible_positions_king_checked, is_cell_color_ally, is_valid,
-};
+use crate::moves::{CastlingRights, Move};
+use crate::utils::{cleaned_positions, is_cell_color_ally, is_valid};
 pub struct Knight;
 
 impl Movable for Knight {
@@ -10,7 +9,7 @@ impl Movable for Knight {
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
         allow_move_on_ally_positions: bool,
-        _move_history: Vec<(Option<PieceType>, String)>,
+        _move_history: &[Move],
     ) -> Vec<Vec<i8>> {
         let mut positions: Vec<Vec<i8>> = Vec::new();
 
@@ -51,15 +50,15 @@ impl Position for Knight {
         coordinates: [i8; 2],
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
-        move_history: Vec<(Option<PieceType>, String)>,
-        _is_king_checked: bool,
+        move_history: &[Move],
+        castling_rights: CastlingRights,
     ) -> Vec<Vec<i8>> {
-        impossible_positions_king_checked(
+        PieceType::Knight.authorized_positions(
             coordinates,
-            Self::piece_move(coordinates, color, board, false, move_history.clone()),
-            board,
             color,
+            board,
             move_history,
+            castling_rights,
         )
     }
 
@@ -67,7 +66,7 @@ impl Position for Knight {
         coordinates: [i8; 2],
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
-        _move_history: Vec<(Option<PieceType>, String)>,
+        _move_history: &[Move],
     ) -> Vec<Vec<i8>> {
         Self::piece_move(coordinates, color, board, true, _move_history)
     }
@@ -89,8 +88,8 @@ impl Knight {
 mod tests {
     use crate::{
         board::Board,
+        moves::CastlingRights,
         pieces::{knight::Knight, PieceColor, PieceType, Position},
-        utils::is_getting_checked,
     };
 
     #[test]
@@ -129,8 +128,13 @@ mod tests {
         ];
         right_positions.sort();
 
-        let mut positions =
-            Knight::authorized_positions([4, 4], PieceColor::White, board.board, vec![], false);
+        let mut positions = Knight::authorized_positions(
+            [4, 4],
+            PieceColor::White,
+            board.board,
+            &[],
+            CastlingRights::default(),
+        );
         positions.sort();
 
         assert_eq!(right_positions, positions);
@@ -181,8 +185,13 @@ mod tests {
         let mut right_positions = vec![vec![6, 5]];
         right_positions.sort();
 
-        let mut positions =
-            Knight::authorized_positions([7, 7], PieceColor::White, board.board, vec![], false);
+        let mut positions = Knight::authorized_positions(
+            [7, 7],
+            PieceColor::White,
+            board.board,
+            &[],
+            CastlingRights::default(),
+        );
         positions.sort();
 
         assert_eq!(right_positions, positions);
@@ -230,9 +239,6 @@ mod tests {
         let mut board = Board::new(custom_board, PieceColor::White, vec![]);
         board.set_board(custom_board);
 
-        let is_king_checked =
-            is_getting_checked(board.board, board.player_turn, board.moves_history);
-
         let mut right_positions = vec![vec![7, 7]];
         right_positions.sort();
 
@@ -240,8 +246,8 @@ mod tests {
             [6, 5],
             PieceColor::White,
             board.board,
-            vec![],
-            is_king_checked,
+            &[],
+            CastlingRights::default(),
         );
         positions.sort();
 
@@ -290,9 +296,6 @@ mod tests {
         let mut board = Board::new(custom_board, PieceColor::White, vec![]);
         board.set_board(custom_board);
 
-        let is_king_checked =
-            is_getting_checked(board.board, board.player_turn, board.moves_history);
-
         let mut right_positions: Vec<Vec<i8>> = vec![];
         right_positions.sort();
 
@@ -300,8 +303,8 @@ mod tests {
             [6, 4],
             PieceColor::White,
             board.board,
-            vec![],
-            is_king_checked,
+            &[],
+            CastlingRights::default(),
         );
         positions.sort();
 
@@ -349,9 +352,6 @@ mod tests {
         let mut board = Board::new(custom_board, PieceColor::Black, vec![]);
         board.set_board(custom_board);
 
-        let is_king_checked =
-            is_getting_checked(board.board, board.player_turn, board.moves_history);
-
         let mut right_positions: Vec<Vec<i8>> = vec![];
         right_positions.sort();
 
@@ -359,11 +359,11 @@ mod tests {
             [1, 4],
             PieceColor::Black,
             board.board,
-            vec![],
-            is_king_checked,
+            &[],
+            CastlingRights::default(),
         );
         positions.sort();
 
         assert_eq!(right_positions, positions);
     }
-}
\ No newline at end of file
+}
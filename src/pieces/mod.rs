@@ -1,4 +1,8 @@
 use self::{bishop::Bishop, king::King, knight::Knight, pawn::Pawn, queen::Queen, rook::Rook};
+use crate::{
+    bitboard::{square_coordinates, square_index, squares, Bitboards, MoveGenerator},
+    moves::{CastlingRights, Move},
+};
 
 pub mod bishop;
 pub mod king;
@@ -17,45 +21,46 @@ pub enum PieceType {
 }
 
 impl PieceType {
+    // Cells the piece can legally go to, found on bitboards
     pub fn authorized_positions(
         self,
         coordinates: [i8; 2],
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
-        move_history: Vec<(Option<PieceType>, String)>,
-        is_king_checked: bool,
+        move_history: &[Move],
+        castling_rights: CastlingRights,
+    ) -> Vec<Vec<i8>> {
+        let en_passant = move_history
+            .last()
+            .filter(|latest_move| latest_move.piece_color != color)
+            .and_then(|latest_move| latest_move.en_passant_target());
+        let generator = MoveGenerator::new(
+            Bitboards::from_board(&board),
+            color,
+            en_passant,
+            castling_rights,
+        );
+
+        squares(generator.targets(self, square_index(coordinates)))
+            .map(|square| square_coordinates(square).to_vec())
+            .collect()
+    }
+
+    // Cells the piece can go to without looking at the safety of its king
+    pub fn reachable_positions(
+        self,
+        coordinates: [i8; 2],
+        color: PieceColor,
+        board: [[Option<(PieceType, PieceColor)>; 8]; 8],
+        move_history: &[Move],
     ) -> Vec<Vec<i8>> {
         match self {
-            PieceType::Pawn => {
-                Pawn::authorized_positions(coordinates, color, board, move_history, is_king_checked)
-            }
-            PieceType::Rook => {
-                Rook::authorized_positions(coordinates, color, board, move_history, is_king_checked)
-            }
-            PieceType::Bishop => Bishop::authorized_positions(
-                coordinates,
-                color,
-                board,
-                move_history,
-                is_king_checked,
-            ),
-            PieceType::Queen => Queen::authorized_positions(
-                coordinates,
-                color,
-                board,
-                move_history,
-                is_king_checked,
-            ),
-            PieceType::King => {
-                King::authorized_positions(coordinates, color, board, move_history, is_king_checked)
-            }
-            PieceType::Knight => Knight::authorized_positions(
-                coordinates,
-                color,
-                board,
-                move_history,
-                is_king_checked,
-            ),
+            PieceType::Pawn => Pawn::piece_move(coordinates, color, board, false, move_history),
+            PieceType::Rook => Rook::piece_move(coordinates, color, board, false, move_history),
+            PieceType::Bishop => Bishop::piece_move(coordinates, color, board, false, move_history),
+            PieceType::Queen => Queen::piece_move(coordinates, color, board, false, move_history),
+            PieceType::King => King::piece_move(coordinates, color, board, false, move_history),
+            PieceType::Knight => Knight::piece_move(coordinates, color, board, false, move_history),
         }
     }
 
@@ -64,7 +69,7 @@ impl PieceType {
         piece_type: PieceType,
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
-        move_history: Vec<(Option<PieceType>, String)>,
+        move_history: &[Move],
     ) -> Vec<Vec<i8>> {
         match piece_type {
             PieceType::Pawn => {
@@ -101,7 +106,7 @@ pub trait Movable {
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
         allow_move_on_ally_positions: bool,
-        move_history: Vec<(Option<PieceType>, String)>,
+        move_history: &[Move],
     ) -> Vec<Vec<i8>>;
 }
 
@@ -110,14 +115,202 @@ pub trait Position {
         coordinates: [i8; 2],
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
-        move_history: Vec<(Option<PieceType>, String)>,
-        is_king_checked: bool,
+        move_history: &[Move],
+        castling_rights: CastlingRights,
     ) -> Vec<Vec<i8>>;
 
     fn protected_positions(
         coordinates: [i8; 2],
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
-        move_history: Vec<(Option<PieceType>, String)>,
+        move_history: &[Move],
     ) -> Vec<Vec<i8>>;
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{PieceColor, PieceType};
+    use crate::{
+        board::Board,
+        moves::{home_line, CastlingRights, CastlingSide, Move},
+        utils::get_king_coordinates,
+    };
+
+    // The generator the bitboards replaced: every cell a piece reaches is kept when its king
+    // is not attacked once the move is played on a copy of the board
+    fn attacked_cells(
+        board: [[Option<(PieceType, PieceColor)>; 8]; 8],
+        color: PieceColor,
+        move_history: &[Move],
+    ) -> Vec<Vec<i8>> {
+        let mut cells = vec![];
+        for y in 0..8i8 {
+            for x in 0..8i8 {
+                if let Some((piece_type, piece_color)) = board[y as usize][x as usize] {
+                    if piece_color != color {
+                        cells.extend(PieceType::protected_positions(
+                            [y, x],
+                            piece_type,
+                            piece_color,
+                            board,
+                            move_history,
+                        ));
+                    }
+                }
+            }
+        }
+        cells
+    }
+
+    fn is_checked_after(
+        board: [[Option<(PieceType, PieceColor)>; 8]; 8],
+        from: [i8; 2],
+        to: &[i8],
+        color: PieceColor,
+        move_history: &[Move],
+    ) -> bool {
+        let mut new_board = board;
+        let piece = new_board[from[0] as usize][from[1] as usize].take();
+        // A pawn going on an empty cell diagonally takes en passant
+        if matches!(piece, Some((PieceType::Pawn, _)))
+            && from[1] != to[1]
+            && new_board[to[0] as usize][to[1] as usize].is_none()
+        {
+            new_board[from[0] as usize][to[1] as usize] = None;
+        }
+        new_board[to[0] as usize][to[1] as usize] = piece;
+        let king = get_king_coordinates(new_board, color).to_vec();
+        attacked_cells(new_board, color, move_history).contains(&king)
+    }
+
+    fn can_castle(
+        board: [[Option<(PieceType, PieceColor)>; 8]; 8],
+        [king_line, king_x]: [i8; 2],
+        color: PieceColor,
+        rook_x: i8,
+        side: CastlingSide,
+        move_history: &[Move],
+    ) -> bool {
+        if king_line != home_line(color)
+            || board[king_line as usize][rook_x as usize] != Some((PieceType::Rook, color))
+        {
+            return false;
+        }
+        let king_path =
+            king_x.min(side.king_destination_x())..=king_x.max(side.king_destination_x());
+        let rook_path =
+            rook_x.min(side.rook_destination_x())..=rook_x.max(side.rook_destination_x());
+
+        let mut castling_board = board;
+        castling_board[king_line as usize][king_x as usize] = None;
+        castling_board[king_line as usize][rook_x as usize] = None;
+        if king_path
+            .clone()
+            .chain(rook_path)
+            .any(|x| castling_board[king_line as usize][x as usize].is_some())
+        {
+            return false;
+        }
+        let checked_cells = attacked_cells(castling_board, color, move_history);
+        king_path
+            .into_iter()
+            .all(|x| !checked_cells.contains(&vec![king_line, x]))
+    }
+
+    fn reference_positions(
+        piece_type: PieceType,
+        coordinates: [i8; 2],
+        color: PieceColor,
+        board: [[Option<(PieceType, PieceColor)>; 8]; 8],
+        move_history: &[Move],
+        castling_rights: CastlingRights,
+    ) -> Vec<Vec<i8>> {
+        let mut positions: Vec<Vec<i8>> = piece_type
+            .reachable_positions(coordinates, color, board, move_history)
+            .into_iter()
+            .filter(|to| !is_checked_after(board, coordinates, to, color, move_history))
+            .collect();
+
+        let king = get_king_coordinates(board, color).to_vec();
+        if piece_type == PieceType::King
+            && !attacked_cells(board, color, move_history).contains(&king)
+        {
+            for side in [CastlingSide::Queen, CastlingSide::King] {
+                if let Some(rook_x) = castling_rights.rook_x(color, side) {
+                    if can_castle(board, coordinates, color, rook_x, side, move_history) {
+                        positions.push(vec![coordinates[0], rook_x]);
+                    }
+                }
+            }
+        }
+        positions
+    }
+
+    // Compare both generators on every piece of the player to play, in the position and in
+    // the positions reached after one and two moves
+    fn assert_same_positions(board: &mut Board, history: &mut Vec<Move>, depth: u32) {
+        let color = board.player_turn;
+        for y in 0..8i8 {
+            for x in 0..8i8 {
+                let Some((piece_type, piece_color)) = board.board[y as usize][x as usize] else {
+                    continue;
+                };
+                if piece_color != color {
+                    continue;
+                }
+                let mut positions = piece_type.authorized_positions(
+                    [y, x],
+                    color,
+                    board.board,
+                    history,
+                    board.castling_rights,
+                );
+                let mut expected = reference_positions(
+                    piece_type,
+                    [y, x],
+                    color,
+                    board.board,
+                    history,
+                    board.castling_rights,
+                );
+                positions.sort();
+                expected.sort();
+                assert_eq!(
+                    positions,
+                    expected,
+                    "{:?} on {:?} in {}",
+                    piece_type,
+                    [y, x],
+                    board.to_fen()
+                );
+            }
+        }
+        if depth == 0 {
+            return;
+        }
+        for piece_move in board.legal_moves() {
+            let undo = board.make_move(&piece_move);
+            history.push(piece_move);
+            assert_same_positions(board, history, depth - 1);
+            history.pop();
+            board.unmake_move(&piece_move, undo);
+        }
+    }
+
+    #[test]
+    fn same_positions_as_the_previous_generator() {
+        for fen in [
+            "rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1",
+            "r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1",
+            "8/2p5/3p4/KP5r/1R3p1k/8/4P1P1/8 w - - 0 1",
+            "r3k2r/Pppp1ppp/1b3nbN/nP6/BBP1P3/q4N2/Pp1P2PP/R2Q1RK1 w kq - 0 1",
+            "bqnb1rkr/pp3ppp/3ppn2/2p5/5P2/P2P4/NPP1P1PP/BQ1BNRKR w HFhf - 2 9",
+            "rnbq1k1r/pp1Pbppp/2p5/8/2B5/8/PPP1NnPP/RNBQK2R w KQ - 1 8",
+            "r4rk1/1pp1qppp/p1np1n2/2b1p1B1/2B1P1b1/P1NP1N2/1PP1QPPP/R4RK1 w - - 0 10",
+        ] {
+            let mut board = Board::from_fen(fen).unwrap();
+            let mut history = board.rules_history();
+            assert_same_positions(&mut board, &mut history, 2);
+        }
+    }
+}
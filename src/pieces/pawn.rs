@@ -1,7 +1,7 @@
 use super::{Movable, PieceColor, PieceType, Position};
+use crate::moves::{CastlingRights, Move, MoveKind};
 use crate::utils::{
-    cleaned_positions, get_int_from_char, get_latest_move, get_piece_color,
-    impossible_positions_king_checked, is_cell_color_ally, is_valid,
+    cleaned_positions, get_latest_move, get_piece_color, is_cell_color_ally, is_valid,
 };
 
 pub struct Pawn;
@@ -12,7 +12,7 @@ impl Movable for Pawn {
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
         allow_move_on_ally_positions: bool,
-        move_history: Vec<(Option<PieceType>, String)>,
+        move_history: &[Move],
     ) -> Vec<Vec<i8>> {
         // Pawns can only move in one direction depending of their color
         // -1 if they are white (go up) +1 if they are black (go down)
@@ -84,40 +84,18 @@ impl Movable for Pawn {
         }
 
         // We check for en passant
-        let latest_move = get_latest_move(&move_history);
-
-        match latest_move {
-            (Some(PieceType::Pawn), piece_move) => {
-                let from_y = get_int_from_char(piece_move.chars().nth(0));
-                let from_x = get_int_from_char(piece_move.chars().nth(1));
-                let to_y = get_int_from_char(piece_move.chars().nth(2));
-                let to_x = get_int_from_char(piece_move.chars().nth(3));
-
-                let valid_y_start: i8;
-                let number_of_cells_move: i8;
-
-                if color == PieceColor::White {
-                    valid_y_start = 1;
-                    number_of_cells_move = to_y - from_y;
-                } else {
-                    valid_y_start = 6;
-                    number_of_cells_move = from_y - to_y;
-                };
-
-                // We check if the latest move was on the right start cell
-                // if it moved 2 cells
-                // and if the current pawn is next to this pawn latest position
-                if from_y == valid_y_start
-                    && number_of_cells_move == 2
-                    && y == to_y
-                    && (x == to_x - 1 || x == to_x + 1)
-                {
-                    let new_y = from_y + -direction;
-                    let new_x = from_x;
-                    positions.push([new_y, new_x].to_vec());
-                }
+        if let Some(latest_move) = get_latest_move(move_history) {
+            // We check if the latest move was an enemy pawn moving 2 cells
+            // and if the current pawn is next to this pawn latest position
+            if latest_move.kind == MoveKind::DoublePawnPush
+                && latest_move.piece_color != color
+                && y == latest_move.to.y
+                && (x == latest_move.to.x - 1 || x == latest_move.to.x + 1)
+            {
+                let new_y = latest_move.from.y + -direction;
+                let new_x = latest_move.from.x;
+                positions.push([new_y, new_x].to_vec());
             }
-            _ => {}
         }
 
         cleaned_positions(positions)
@@ -129,17 +107,15 @@ impl Position for Pawn {
         coordinates: [i8; 2],
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
-        move_history: Vec<(Option<PieceType>, String)>,
-        _is_king_checked: bool,
+        move_history: &[Move],
+        castling_rights: CastlingRights,
     ) -> Vec<Vec<i8>> {
-        // If the king is not checked we get then normal moves
-        // if the king is checked we clean all the position not resolving the check
-        impossible_positions_king_checked(
+        PieceType::Pawn.authorized_positions(
             coordinates,
-            Self::piece_move(coordinates, color, board, false, move_history.clone()),
-            board,
             color,
+            board,
             move_history,
+            castling_rights,
         )
     }
 
@@ -147,7 +123,7 @@ impl Position for Pawn {
         coordinates: [i8; 2],
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
-        move_history: Vec<(Option<PieceType>, String)>,
+        move_history: &[Move],
     ) -> Vec<Vec<i8>> {
         Self::piece_move(coordinates, color, board, true, move_history)
     }
@@ -169,8 +145,8 @@ impl Pawn {
 mod tests {
     use crate::{
         board::Board,
+        moves::{CastlingRights, Move},
         pieces::{pawn::Pawn, PieceColor, PieceType, Position},
-        utils::is_getting_checked,
     };
 
     #[test]
@@ -204,8 +180,8 @@ mod tests {
             [4, 4],
             PieceColor::White,
             board.board,
-            vec![(None, "0000".to_string())],
-            false,
+            &[],
+            CastlingRights::default(),
         );
         positions.sort();
         assert_eq!(right_positions, positions);
@@ -242,8 +218,8 @@ mod tests {
             [6, 4],
             PieceColor::White,
             board.board,
-            vec![(None, "0000".to_string())],
-            false,
+            &[],
+            CastlingRights::default(),
         );
         positions.sort();
         assert_eq!(right_positions, positions);
@@ -289,8 +265,8 @@ mod tests {
             [1, 3],
             PieceColor::Black,
             board.board,
-            vec![(None, "0000".to_string())],
-            false,
+            &[],
+            CastlingRights::default(),
         );
         positions.sort();
         assert_eq!(right_positions, positions);
@@ -336,8 +312,8 @@ mod tests {
             [1, 3],
             PieceColor::Black,
             board.board,
-            vec![(None, "0000".to_string())],
-            false,
+            &[],
+            CastlingRights::default(),
         );
         positions.sort();
         assert_eq!(right_positions, positions);
@@ -374,8 +350,13 @@ mod tests {
             [3, 3],
             PieceColor::White,
             board.board,
-            vec![(Some(PieceType::Pawn), "1232".to_string())],
-            false,
+            &[Move::new(
+                PieceType::Pawn,
+                PieceColor::Black,
+                [1, 2],
+                [3, 2],
+            )],
+            CastlingRights::default(),
         );
         positions.sort();
         assert_eq!(right_positions, positions);
@@ -412,8 +393,13 @@ mod tests {
             [4, 2],
             PieceColor::Black,
             board.board,
-            vec![(Some(PieceType::Pawn), "6343".to_string())],
-            false,
+            &[Move::new(
+                PieceType::Pawn,
+                PieceColor::White,
+                [6, 3],
+                [4, 3],
+            )],
+            CastlingRights::default(),
         );
         positions.sort();
         assert_eq!(right_positions, positions);
@@ -459,8 +445,13 @@ mod tests {
             [1, 1],
             PieceColor::Black,
             board.board,
-            vec![(Some(PieceType::Pawn), "6343".to_string())],
-            false,
+            &[Move::new(
+                PieceType::Pawn,
+                PieceColor::White,
+                [6, 3],
+                [4, 3],
+            )],
+            CastlingRights::default(),
         );
         positions.sort();
         assert_eq!(right_positions, positions);
@@ -499,9 +490,6 @@ mod tests {
         let mut board = Board::new(custom_board, PieceColor::Black, vec![]);
         board.set_board(custom_board);
 
-        let is_king_checked =
-            is_getting_checked(board.board, board.player_turn, board.moves_history);
-
         let mut right_positions = vec![vec![3, 2]];
         right_positions.sort();
 
@@ -509,8 +497,8 @@ mod tests {
             [2, 3],
             PieceColor::Black,
             board.board,
-            vec![],
-            is_king_checked,
+            &[],
+            CastlingRights::default(),
         );
         positions.sort();
 
@@ -550,9 +538,6 @@ mod tests {
         let mut board = Board::new(custom_board, PieceColor::Black, vec![]);
         board.set_board(custom_board);
 
-        let is_king_checked =
-            is_getting_checked(board.board, board.player_turn, board.moves_history);
-
         let mut right_positions: Vec<Vec<i8>> = vec![];
         right_positions.sort();
 
@@ -560,8 +545,8 @@ mod tests {
             [2, 4],
             PieceColor::Black,
             board.board,
-            vec![],
-            is_king_checked,
+            &[],
+            CastlingRights::default(),
         );
         positions.sort();
 
@@ -610,9 +595,6 @@ mod tests {
         let mut board = Board::new(custom_board, PieceColor::Black, vec![]);
         board.set_board(custom_board);
 
-        let is_king_checked =
-            is_getting_checked(board.board, board.player_turn, board.moves_history);
-
         let mut right_positions: Vec<Vec<i8>> = vec![];
         right_positions.sort();
 
@@ -620,11 +602,11 @@ mod tests {
             [1, 5],
             PieceColor::Black,
             board.board,
-            vec![],
-            is_king_checked,
+            &[],
+            CastlingRights::default(),
         );
         positions.sort();
 
         assert_eq!(right_positions, positions);
     }
-}
\ No newline at end of file
+}
@@ -1,7 +1,8 @@
 use super::rook::Rook;
 use super::{Movable, PieceColor, PieceType, Position};
+use crate::moves::{CastlingRights, Move};
 use crate::pieces::bishop::Bishop;
-use crate::utils::{cleaned_positions, impossible_positions_king_checked};
+use crate::utils::cleaned_positions;
 
 pub struct Queen;
 
@@ -11,7 +12,7 @@ impl Movable for Queen {
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
         allow_move_on_ally_positions: bool,
-        _move_history: Vec<(Option<PieceType>, String)>,
+        _move_history: &[Move],
     ) -> Vec<Vec<i8>> {
         let mut positions: Vec<Vec<i8>> = vec![];
 
@@ -21,7 +22,7 @@ impl Movable for Queen {
             color,
             board,
             allow_move_on_ally_positions,
-            _move_history.clone(),
+            _move_history,
         ));
         positions.extend(Rook::piece_move(
             coordinates,
@@ -40,22 +41,23 @@ impl Position for Queen {
         coordinates: [i8; 2],
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
-        move_history: Vec<(Option<PieceType>, String)>,
-        _is_king_checked: bool,
+        move_history: &[Move],
+        castling_rights: CastlingRights,
     ) -> Vec<Vec<i8>> {
-        impossible_positions_king_checked(
+        PieceType::Queen.authorized_positions(
             coordinates,
-            Self::piece_move(coordinates, color, board, false, move_history.clone()),
-            board,
             color,
+            board,
             move_history,
+            castling_rights,
         )
     }
+
     fn protected_positions(
         coordinates: [i8; 2],
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
-        _move_history: Vec<(Option<PieceType>, String)>,
+        _move_history: &[Move],
     ) -> Vec<Vec<i8>> {
         Self::piece_move(coordinates, color, board, true, _move_history)
     }
@@ -77,8 +79,8 @@ impl Queen {
 mod tests {
     use crate::{
         board::Board,
+        moves::CastlingRights,
         pieces::{queen::Queen, PieceColor, PieceType, Position},
-        utils::is_getting_checked,
     };
 
     #[test]
@@ -136,8 +138,13 @@ mod tests {
         ];
         right_positions.sort();
 
-        let mut positions =
-            Queen::authorized_positions([4, 4], PieceColor::White, board.board, vec![], false);
+        let mut positions = Queen::authorized_positions(
+            [4, 4],
+            PieceColor::White,
+            board.board,
+            &[],
+            CastlingRights::default(),
+        );
         positions.sort();
 
         assert_eq!(right_positions, positions);
@@ -205,8 +212,13 @@ mod tests {
         ];
         right_positions.sort();
 
-        let mut positions =
-            Queen::authorized_positions([4, 4], PieceColor::White, board.board, vec![], false);
+        let mut positions = Queen::authorized_positions(
+            [4, 4],
+            PieceColor::White,
+            board.board,
+            &[],
+            CastlingRights::default(),
+        );
         positions.sort();
 
         assert_eq!(right_positions, positions);
@@ -284,8 +296,13 @@ mod tests {
 
         right_positions.sort();
 
-        let mut positions =
-            Queen::authorized_positions([4, 4], PieceColor::White, board.board, vec![], false);
+        let mut positions = Queen::authorized_positions(
+            [4, 4],
+            PieceColor::White,
+            board.board,
+            &[],
+            CastlingRights::default(),
+        );
         positions.sort();
 
         assert_eq!(right_positions, positions);
@@ -332,9 +349,6 @@ mod tests {
         let mut board = Board::new(custom_board, PieceColor::Black, vec![]);
         board.set_board(custom_board);
 
-        let is_king_checked =
-            is_getting_checked(board.board, board.player_turn, board.moves_history);
-
         let mut right_positions = vec![vec![4, 4]];
         right_positions.sort();
 
@@ -342,8 +356,8 @@ mod tests {
             [5, 5],
             PieceColor::Black,
             board.board,
-            vec![],
-            is_king_checked,
+            &[],
+            CastlingRights::default(),
         );
         positions.sort();
 
@@ -391,9 +405,6 @@ mod tests {
         let mut board = Board::new(custom_board, PieceColor::Black, vec![]);
         board.set_board(custom_board);
 
-        let is_king_checked =
-            is_getting_checked(board.board, board.player_turn, board.moves_history);
-
         let mut right_positions: Vec<Vec<i8>> = vec![];
         right_positions.sort();
 
@@ -401,8 +412,8 @@ mod tests {
             [5, 6],
             PieceColor::Black,
             board.board,
-            vec![],
-            is_king_checked,
+            &[],
+            CastlingRights::default(),
         );
         positions.sort();
 
@@ -451,9 +462,6 @@ mod tests {
         let mut board = Board::new(custom_board, PieceColor::Black, vec![]);
         board.set_board(custom_board);
 
-        let is_king_checked =
-            is_getting_checked(board.board, board.player_turn, board.moves_history);
-
         let mut right_positions: Vec<Vec<i8>> = vec![vec![2, 6], vec![3, 7]];
         right_positions.sort();
 
@@ -461,11 +469,11 @@ mod tests {
             [1, 5],
             PieceColor::Black,
             board.board,
-            vec![],
-            is_king_checked,
+            &[],
+            CastlingRights::default(),
         );
         positions.sort();
 
         assert_eq!(right_positions, positions);
     }
-}
\ No newline at end of file
+}
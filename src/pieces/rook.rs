@@ -1,7 +1,7 @@
 use super::{Movable, PieceColor, PieceType, Position};
+use crate::moves::{CastlingRights, Move};
 use crate::utils::{
-    cleaned_positions, get_piece_color, impossible_positions_king_checked, is_cell_color_ally,
-    is_piece_opposite_king, is_valid,
+    cleaned_positions, get_piece_color, is_cell_color_ally, is_piece_opposite_king, is_valid,
 };
 
 pub struct Rook;
@@ -12,7 +12,7 @@ impl Movable for Rook {
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
         allow_move_on_ally_positions: bool,
-        _move_history: Vec<(Option<PieceType>, String)>,
+        _move_history: &[Move],
     ) -> Vec<Vec<i8>> {
         // Pawns can only move in one direction depending on their color
         let mut positions: Vec<Vec<i8>> = vec![];
@@ -170,17 +170,15 @@ impl Position for Rook {
         coordinates: [i8; 2],
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
-        move_history: Vec<(Option<PieceType>, String)>,
-        _is_king_checked: bool,
+        move_history: &[Move],
+        castling_rights: CastlingRights,
     ) -> Vec<Vec<i8>> {
-        // If the king is not checked we get then normal moves
-        // if the king is checked we clean all the position not resolving the check
-        impossible_positions_king_checked(
+        PieceType::Rook.authorized_positions(
             coordinates,
-            Self::piece_move(coordinates, color, board, false, move_history.clone()),
-            board,
             color,
+            board,
             move_history,
+            castling_rights,
         )
     }
 
@@ -188,7 +186,7 @@ impl Position for Rook {
         coordinates: [i8; 2],
         color: PieceColor,
         board: [[Option<(PieceType, PieceColor)>; 8]; 8],
-        _move_history: Vec<(Option<PieceType>, String)>,
+        _move_history: &[Move],
     ) -> Vec<Vec<i8>> {
         Self::piece_move(coordinates, color, board, true, _move_history)
     }
@@ -210,8 +208,8 @@ impl Rook {
 mod tests {
     use crate::{
         board::Board,
+        moves::CastlingRights,
         pieces::{rook::Rook, PieceColor, PieceType, Position},
-        utils::is_getting_checked,
     };
 
     #[test]
@@ -256,8 +254,13 @@ mod tests {
         ];
         right_positions.sort();
 
-        let mut positions =
-            Rook::authorized_positions([4, 4], PieceColor::White, board.board, vec![], false);
+        let mut positions = Rook::authorized_positions(
+            [4, 4],
+            PieceColor::White,
+            board.board,
+            &[],
+            CastlingRights::default(),
+        );
         positions.sort();
         assert_eq!(right_positions, positions);
     }
@@ -310,8 +313,13 @@ mod tests {
         ];
         right_positions.sort();
 
-        let mut positions =
-            Rook::authorized_positions([4, 4], PieceColor::White, board.board, vec![], false);
+        let mut positions = Rook::authorized_positions(
+            [4, 4],
+            PieceColor::White,
+            board.board,
+            &[],
+            CastlingRights::default(),
+        );
         positions.sort();
         assert_eq!(right_positions, positions);
     }
@@ -370,8 +378,13 @@ mod tests {
         ];
         right_positions.sort();
 
-        let mut positions =
-            Rook::authorized_positions([4, 4], PieceColor::White, board.board, vec![], false);
+        let mut positions = Rook::authorized_positions(
+            [4, 4],
+            PieceColor::White,
+            board.board,
+            &[],
+            CastlingRights::default(),
+        );
         positions.sort();
 
         assert_eq!(right_positions, positions);
@@ -419,9 +432,6 @@ mod tests {
         let mut board = Board::new(custom_board, PieceColor::Black, vec![]);
         board.set_board(custom_board);
 
-        let is_king_checked =
-            is_getting_checked(board.board, board.player_turn, board.moves_history);
-
         let mut right_positions = vec![vec![4, 2]];
         right_positions.sort();
 
@@ -429,8 +439,8 @@ mod tests {
             [5, 2],
             PieceColor::Black,
             board.board,
-            vec![],
-            is_king_checked,
+            &[],
+            CastlingRights::default(),
         );
         positions.sort();
 
@@ -479,9 +489,6 @@ mod tests {
         let mut board = Board::new(custom_board, PieceColor::Black, vec![]);
         board.set_board(custom_board);
 
-        let is_king_checked =
-            is_getting_checked(board.board, board.player_turn, board.moves_history);
-
         let mut right_positions: Vec<Vec<i8>> = vec![];
         right_positions.sort();
 
@@ -489,8 +496,8 @@ mod tests {
             [5, 3],
             PieceColor::Black,
             board.board,
-            vec![],
-            is_king_checked,
+            &[],
+            CastlingRights::default(),
         );
         positions.sort();
 
@@ -538,9 +545,6 @@ mod tests {
         let mut board = Board::new(custom_board, PieceColor::Black, vec![]);
         board.set_board(custom_board);
 
-        let is_king_checked =
-            is_getting_checked(board.board, board.player_turn, board.moves_history);
-
         let mut right_positions: Vec<Vec<i8>> = vec![vec![2, 4], vec![3, 4]];
         right_positions.sort();
 
@@ -548,11 +552,11 @@ mod tests {
             [1, 4],
             PieceColor::Black,
             board.board,
-            vec![],
-            is_king_checked,
+            &[],
+            CastlingRights::default(),
         );
         positions.sort();
 
         assert_eq!(right_positions, positions);
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,431 @@
+use std::{error::Error, fmt};
+
+use crate::{
+    board::Board,
+    moves::{home_line, CastlingSide, Move},
+    pieces::PieceType,
+    utils::{col_to_letter, coordinates_to_notation, is_getting_checked, notation_to_coordinates},
+};
+
+// From and to coordinates of a move along with the promotion piece
+pub type SanMove = ([i8; 2], [i8; 2], Option<PieceType>);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SanError {
+    // The text is not a move in algebraic notation
+    Invalid,
+    // No piece of the side to move can play it
+    Illegal,
+    // Several pieces can play it and the move doesn't tell which one
+    Ambiguous,
+    // A pawn reaches the last row without a promotion piece
+    MissingPromotion,
+}
+
+impl fmt::Display for SanError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SanError::Invalid => write!(f, "not a valid SAN move"),
+            SanError::Illegal => write!(f, "illegal move"),
+            SanError::Ambiguous => write!(f, "ambiguous move"),
+            SanError::MissingPromotion => write!(f, "missing promotion piece"),
+        }
+    }
+}
+
+impl Error for SanError {}
+
+// Letter used for a piece in SAN, pawns don't have any
+pub fn piece_letter(piece_type: PieceType) -> &'static str {
+    match piece_type {
+        PieceType::Pawn => "",
+        PieceType::Rook => "R",
+        PieceType::Bishop => "B",
+        PieceType::Queen => "Q",
+        PieceType::King => "K",
+        PieceType::Knight => "N",
+    }
+}
+
+pub fn letter_to_piece(letter: char) -> Option<PieceType> {
+    match letter {
+        'R' => Some(PieceType::Rook),
+        'B' => Some(PieceType::Bishop),
+        'Q' => Some(PieceType::Queen),
+        'K' => Some(PieceType::King),
+        'N' => Some(PieceType::Knight),
+        _ => None,
+    }
+}
+
+// Play a move on the board the same way a player does, the turn goes to the other player
+pub fn apply_move(board: &mut Board, from: [i8; 2], to: [i8; 2], promotion: Option<PieceType>) {
+    board.move_piece_on_the_board(
+        [from[0] as usize, from[1] as usize],
+        [to[0] as usize, to[1] as usize],
+        promotion,
+    );
+}
+
+// Standard Algebraic Notation of the move from -> to played in the given position
+pub fn move_to_san(
+    board: &Board,
+    from: [i8; 2],
+    to: [i8; 2],
+    promotion: Option<PieceType>,
+) -> String {
+    let Some((piece_type, piece_color)) = board.board[from[0] as usize][from[1] as usize] else {
+        return String::new();
+    };
+    let history = board.rules_history();
+    let target = board.board[to[0] as usize][to[1] as usize];
+
+    let mut san = String::new();
+    if board
+        .build_move(from.map(|i| i as usize), to.map(|i| i as usize))
+        .is_castling()
+    {
+        // The king goes on its rook when castling
+        san += if to[1] > from[1] { "O-O" } else { "O-O-O" };
+    } else if piece_type == PieceType::Pawn {
+        // A pawn changing column always captures, even en passant
+        if from[1] != to[1] {
+            san += &col_to_letter(from[1]);
+            san += "x";
+        }
+        san += &coordinates_to_notation(to);
+        if let Some(promotion) = promotion {
+            san += "=";
+            san += piece_letter(promotion);
+        }
+    } else {
+        san += piece_letter(piece_type);
+
+        // Other pieces of the same kind that can also go there
+        let mut rivals: Vec<[i8; 2]> = vec![];
+        for i in 0..8i8 {
+            for j in 0..8i8 {
+                if [i, j] != from
+                    && board.board[i as usize][j as usize] == Some((piece_type, piece_color))
+                    && piece_type
+                        .authorized_positions(
+                            [i, j],
+                            piece_color,
+                            board.board,
+                            &history,
+                            board.castling_rights,
+                        )
+                        .contains(&to.to_vec())
+                {
+                    rivals.push([i, j]);
+                }
+            }
+        }
+        if !rivals.is_empty() {
+            if rivals.iter().all(|rival| rival[1] != from[1]) {
+                san += &col_to_letter(from[1]);
+            } else if rivals.iter().all(|rival| rival[0] != from[0]) {
+                san += &(8 - from[0]).to_string();
+            } else {
+                san += &coordinates_to_notation(from);
+            }
+        }
+
+        if matches!(target, Some((_, target_color)) if target_color != piece_color) {
+            san += "x";
+        }
+        san += &coordinates_to_notation(to);
+    }
+
+    // We play the move to know if it checks or mates
+    let mut next_board = Board::new(board.board, piece_color, history);
+    apply_move(&mut next_board, from, to, promotion);
+    if next_board.is_checkmate() {
+        san += "#";
+    } else if is_getting_checked(next_board.board, next_board.player_turn) {
+        san += "+";
+    }
+
+    san
+}
+
+// Find the move a SAN string stands for in the given position
+pub fn san_to_move(board: &Board, san: &str) -> Result<SanMove, SanError> {
+    let color = board.player_turn;
+    let history = board.rules_history();
+    let legal_moves = |from: [i8; 2], piece_type: PieceType| {
+        piece_type.authorized_positions(from, color, board.board, &history, board.castling_rights)
+    };
+
+    // Check, mate and annotation symbols don't change the move
+    let san = san.trim_end_matches(['+', '#', '!', '?']);
+
+    // Castling, the king goes on the rook it still has the right to castle with
+    let castling_side = match san {
+        "O-O" | "0-0" => Some(CastlingSide::King),
+        "O-O-O" | "0-0-0" => Some(CastlingSide::Queen),
+        _ => None,
+    };
+    if let Some(side) = castling_side {
+        let line = home_line(color);
+        let king_x = (0..8)
+            .find(|&x| board.board[line as usize][x as usize] == Some((PieceType::King, color)));
+        if let (Some(king_x), Some(rook_x)) = (king_x, board.castling_rights.rook_x(color, side)) {
+            if legal_moves([line, king_x], PieceType::King).contains(&vec![line, rook_x]) {
+                return Ok(([line, king_x], [line, rook_x], None));
+            }
+        }
+        return Err(SanError::Illegal);
+    }
+
+    let mut chars: Vec<char> = san.chars().collect();
+    let piece_type = match chars.first().copied().and_then(letter_to_piece) {
+        Some(piece_type) => {
+            chars.remove(0);
+            piece_type
+        }
+        None => PieceType::Pawn,
+    };
+
+    // Promotion piece, written "e8=Q" or "e8Q"
+    let mut promotion = None;
+    if let Some(letter) = chars
+        .last()
+        .copied()
+        .filter(|letter| letter.is_ascii_uppercase())
+    {
+        if piece_type != PieceType::Pawn {
+            return Err(SanError::Invalid);
+        }
+        match letter_to_piece(letter) {
+            Some(PieceType::King) | None => return Err(SanError::Invalid),
+            Some(piece) => promotion = Some(piece),
+        }
+        chars.pop();
+        if chars.last() == Some(&'=') {
+            chars.pop();
+        }
+    }
+
+    if chars.len() < 2 {
+        return Err(SanError::Invalid);
+    }
+    let destination: String = chars.split_off(chars.len() - 2).into_iter().collect();
+    let to = notation_to_coordinates(&destination).ok_or(SanError::Invalid)?;
+    if chars.last() == Some(&'x') {
+        chars.pop();
+    }
+
+    // What is left tells apart pieces that can go to the same cell
+    let mut from_x = None;
+    let mut from_y = None;
+    for hint in chars {
+        match hint {
+            'a'..='h' if from_x.is_none() && from_y.is_none() => {
+                from_x = Some(hint as i8 - 'a' as i8)
+            }
+            '1'..='8' if from_y.is_none() => from_y = Some(8 - (hint as i8 - '0' as i8)),
+            _ => return Err(SanError::Invalid),
+        }
+    }
+
+    let mut candidates: Vec<[i8; 2]> = vec![];
+    for i in 0..8i8 {
+        for j in 0..8i8 {
+            if board.board[i as usize][j as usize] != Some((piece_type, color))
+                || from_y.is_some_and(|y| y != i)
+                || from_x.is_some_and(|x| x != j)
+                // A king going on its own rook is castling, which has its own notation
+                || (piece_type == PieceType::King
+                    && board.board[to[0] as usize][to[1] as usize] == Some((PieceType::Rook, color)))
+            {
+                continue;
+            }
+            if legal_moves([i, j], piece_type).contains(&to.to_vec()) {
+                candidates.push([i, j]);
+            }
+        }
+    }
+
+    let from = match candidates[..] {
+        [] => return Err(SanError::Illegal),
+        [from] => from,
+        _ => return Err(SanError::Ambiguous),
+    };
+
+    let reaches_last_row = piece_type == PieceType::Pawn && (to[0] == 0 || to[0] == 7);
+    match (reaches_last_row, promotion) {
+        (true, None) => Err(SanError::MissingPromotion),
+        (false, Some(_)) => Err(SanError::Invalid),
+        _ => Ok((from, to, promotion)),
+    }
+}
+
+impl Board {
+    // SAN of every move of the game, replayed from the starting position
+    pub fn moves_san(&self) -> Vec<String> {
+        let mut board = self.starting_board();
+        let mut moves: Vec<String> = vec![];
+
+        for piece_move in &self.moves_history {
+            let (from, to) = (piece_move.from.coordinates(), piece_move.to.coordinates());
+            moves.push(move_to_san(&board, from, to, piece_move.promotion));
+            apply_move(&mut board, from, to, piece_move.promotion);
+        }
+        moves
+    }
+
+    // SAN of a line of moves played from this position
+    pub fn line_san(&self, line: &[Move]) -> Vec<String> {
+        let mut board = self.clone();
+        let mut moves: Vec<String> = vec![];
+
+        for piece_move in line {
+            let (from, to) = (piece_move.from.coordinates(), piece_move.to.coordinates());
+            moves.push(move_to_san(&board, from, to, piece_move.promotion));
+            apply_move(&mut board, from, to, piece_move.promotion);
+        }
+        moves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        board::Board,
+        pieces::{PieceColor, PieceType},
+        san::{apply_move, move_to_san, san_to_move, SanError},
+    };
+
+    fn play(board: &mut Board, moves: &[([i8; 2], [i8; 2])]) -> Vec<String> {
+        let mut sans = vec![];
+        for (from, to) in moves {
+            sans.push(move_to_san(board, *from, *to, None));
+            apply_move(board, *from, *to, None);
+        }
+        sans
+    }
+
+    #[test]
+    fn opening_moves() {
+        let mut board = Board::standard();
+        let sans = play(
+            &mut board,
+            &[
+                ([6, 4], [4, 4]),
+                ([1, 4], [3, 4]),
+                ([7, 6], [5, 5]),
+                ([0, 1], [2, 2]),
+                ([7, 5], [4, 2]),
+                ([0, 6], [2, 5]),
+                ([7, 4], [7, 7]),
+            ],
+        );
+
+        assert_eq!(sans, vec!["e4", "e5", "Nf3", "Nc6", "Bc4", "Nf6", "O-O"]);
+        assert_eq!(board.moves_san(), sans);
+    }
+
+    #[test]
+    fn captures_checks_and_mate() {
+        // Scholar's mate
+        let mut board = Board::standard();
+        let sans = play(
+            &mut board,
+            &[
+                ([6, 4], [4, 4]),
+                ([1, 4], [3, 4]),
+                ([7, 3], [3, 7]),
+                ([0, 1], [2, 2]),
+                ([7, 5], [4, 2]),
+                ([0, 6], [2, 5]),
+                ([3, 7], [1, 5]),
+            ],
+        );
+
+        assert_eq!(sans.last().unwrap(), "Qxf7#");
+
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/R3K3 w Q - 0 1").unwrap();
+        assert_eq!(play(&mut board, &[([7, 0], [0, 0])]), vec!["Ra8+"]);
+    }
+
+    #[test]
+    fn disambiguation() {
+        let mut board = Board::from_fen("4k3/8/8/8/8/8/8/R4RK1 w - - 0 1").unwrap();
+        assert_eq!(move_to_san(&board, [7, 0], [7, 3], None), "Rad1");
+
+        let board_rank = Board::from_fen("R7/7k/8/8/8/8/8/R3K3 w - - 0 1").unwrap();
+        assert_eq!(move_to_san(&board_rank, [7, 0], [4, 0], None), "R1a4");
+
+        let board_square = Board::from_fen("4k3/8/8/8/8/Q7/8/Q1Q1K3 w - - 0 1").unwrap();
+        assert_eq!(move_to_san(&board_square, [7, 0], [6, 1], None), "Qa1b2");
+
+        // A pinned knight doesn't need to be told apart
+        board = Board::from_fen("4k3/4r3/8/8/8/8/4N3/1N2K3 w - - 0 1").unwrap();
+        assert_eq!(move_to_san(&board, [7, 1], [5, 2], None), "Nc3");
+    }
+
+    #[test]
+    fn pawn_moves() {
+        let board =
+            Board::from_fen("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3")
+                .unwrap();
+        assert_eq!(move_to_san(&board, [3, 4], [2, 5], None), "exf6");
+
+        let board = Board::from_fen("3r3k/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            move_to_san(&board, [1, 4], [0, 3], Some(PieceType::Queen)),
+            "exd8=Q+"
+        );
+        assert_eq!(
+            move_to_san(&board, [1, 4], [0, 4], Some(PieceType::Knight)),
+            "e8=N"
+        );
+        assert_eq!(board.player_turn, PieceColor::White);
+    }
+
+    #[test]
+    fn parse_san() {
+        let board = Board::standard();
+        assert_eq!(san_to_move(&board, "e4"), Ok(([6, 4], [4, 4], None)));
+        assert_eq!(san_to_move(&board, "Nf3"), Ok(([7, 6], [5, 5], None)));
+        assert_eq!(san_to_move(&board, "Ngf3!?"), Ok(([7, 6], [5, 5], None)));
+        assert_eq!(san_to_move(&board, "e5"), Err(SanError::Illegal));
+        assert_eq!(san_to_move(&board, "Nd2"), Err(SanError::Illegal));
+        assert_eq!(san_to_move(&board, "O-O"), Err(SanError::Illegal));
+        assert_eq!(san_to_move(&board, "hello"), Err(SanError::Invalid));
+        assert_eq!(san_to_move(&board, "Zf3"), Err(SanError::Invalid));
+        assert_eq!(san_to_move(&board, ""), Err(SanError::Invalid));
+
+        let board = Board::from_fen("r3k2r/p6p/8/8/8/8/P6P/R3K2R b KQkq - 0 1").unwrap();
+        assert_eq!(san_to_move(&board, "O-O"), Ok(([0, 4], [0, 7], None)));
+        assert_eq!(san_to_move(&board, "0-0-0+"), Ok(([0, 4], [0, 0], None)));
+        assert_eq!(san_to_move(&board, "Kh8"), Err(SanError::Illegal));
+
+        let board = Board::from_fen("4k3/8/8/8/8/8/8/R4RK1 w - - 0 1").unwrap();
+        assert_eq!(san_to_move(&board, "Rd1"), Err(SanError::Ambiguous));
+        assert_eq!(san_to_move(&board, "Rad1"), Ok(([7, 0], [7, 3], None)));
+        assert_eq!(san_to_move(&board, "Rfxd1"), Ok(([7, 5], [7, 3], None)));
+    }
+
+    #[test]
+    fn parse_san_pawns() {
+        let board =
+            Board::from_fen("rnbqkbnr/ppp1p1pp/8/3pPp2/8/8/PPPP1PPP/RNBQKBNR w KQkq f6 0 3")
+                .unwrap();
+        assert_eq!(san_to_move(&board, "exf6"), Ok(([3, 4], [2, 5], None)));
+
+        let board = Board::from_fen("3r3k/4P3/8/8/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_eq!(
+            san_to_move(&board, "exd8=Q+"),
+            Ok(([1, 4], [0, 3], Some(PieceType::Queen)))
+        );
+        assert_eq!(
+            san_to_move(&board, "e8N"),
+            Ok(([1, 4], [0, 4], Some(PieceType::Knight)))
+        );
+        assert_eq!(san_to_move(&board, "e8"), Err(SanError::MissingPromotion));
+        assert_eq!(san_to_move(&board, "e8=K"), Err(SanError::Invalid));
+        assert_eq!(san_to_move(&board, "Ke2=Q"), Err(SanError::Invalid));
+    }
+}
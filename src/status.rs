@@ -0,0 +1,122 @@
+use std::fmt;
+
+use crate::pieces::PieceColor;
+
+// State of a game, the winner is given for the decisive endings
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GameStatus {
+    Ongoing,
+    Checkmate { winner: PieceColor },
+    Stalemate,
+    Repetition,
+    // Claimed after fifty moves each
+    FiftyMove,
+    // Automatic after seventy-five moves each
+    SeventyFiveMove,
+    InsufficientMaterial,
+    Resignation { winner: PieceColor },
+    Timeout { winner: PieceColor },
+    Agreement,
+}
+
+impl GameStatus {
+    pub fn is_over(self) -> bool {
+        self != GameStatus::Ongoing
+    }
+
+    pub fn is_draw(self) -> bool {
+        self.is_over() && self.winner().is_none()
+    }
+
+    pub fn winner(self) -> Option<PieceColor> {
+        match self {
+            GameStatus::Checkmate { winner }
+            | GameStatus::Resignation { winner }
+            | GameStatus::Timeout { winner } => Some(winner),
+            _ => None,
+        }
+    }
+
+    // PGN result token, "*" while the game is still going on
+    pub fn result_token(self) -> &'static str {
+        match (self, self.winner()) {
+            (GameStatus::Ongoing, _) => "*",
+            (_, Some(PieceColor::White)) => "1-0",
+            (_, Some(PieceColor::Black)) => "0-1",
+            (_, None) => "1/2-1/2",
+        }
+    }
+}
+
+fn color_name(color: PieceColor) -> &'static str {
+    match color {
+        PieceColor::White => "White",
+        PieceColor::Black => "Black",
+    }
+}
+
+impl fmt::Display for GameStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GameStatus::Ongoing => write!(f, "The game is still going on"),
+            GameStatus::Checkmate { winner } => {
+                write!(f, "{} won by checkmate", color_name(*winner))
+            }
+            GameStatus::Stalemate => write!(f, "That's a draw by stalemate"),
+            GameStatus::Repetition => write!(f, "That's a draw by threefold repetition"),
+            GameStatus::FiftyMove => write!(f, "That's a draw by the fifty-move rule"),
+            GameStatus::SeventyFiveMove => {
+                write!(f, "That's a draw by the seventy-five-move rule")
+            }
+            GameStatus::InsufficientMaterial => {
+                write!(f, "That's a draw by insufficient material")
+            }
+            GameStatus::Resignation { winner } => {
+                write!(f, "{} won by resignation", color_name(*winner))
+            }
+            GameStatus::Timeout { winner } => write!(f, "{} won on time", color_name(*winner)),
+            GameStatus::Agreement => write!(f, "That's a draw by agreement"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{pieces::PieceColor, status::GameStatus};
+
+    #[test]
+    fn result_tokens() {
+        assert_eq!(GameStatus::Ongoing.result_token(), "*");
+        assert_eq!(
+            GameStatus::Checkmate {
+                winner: PieceColor::White
+            }
+            .result_token(),
+            "1-0"
+        );
+        assert_eq!(
+            GameStatus::Timeout {
+                winner: PieceColor::Black
+            }
+            .result_token(),
+            "0-1"
+        );
+        assert_eq!(GameStatus::Repetition.result_token(), "1/2-1/2");
+        assert_eq!(GameStatus::Agreement.result_token(), "1/2-1/2");
+        assert_eq!(GameStatus::SeventyFiveMove.result_token(), "1/2-1/2");
+        assert_ne!(
+            GameStatus::SeventyFiveMove.to_string(),
+            GameStatus::FiftyMove.to_string()
+        );
+        assert!(!GameStatus::Ongoing.is_over());
+        assert!(!GameStatus::Ongoing.is_draw());
+        assert!(GameStatus::Stalemate.is_draw());
+        assert_eq!(
+            GameStatus::Resignation {
+                winner: PieceColor::Black
+            }
+            .to_string(),
+            "Black won by resignation"
+        );
+    }
+}
@@ -0,0 +1,775 @@
+use std::{
+    error::Error,
+    fmt,
+    io::{BufRead, BufReader, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use crate::{
+    board::Board,
+    engine::{search_with_reports, SearchLimits, SearchResult, MATE},
+    moves::{Move, MoveKind},
+    pieces::PieceColor,
+    utils::coordinates_to_notation,
+};
+
+// Time an engine gets to answer the handshake, and to stop once its time to think is over
+const ANSWER_TIME: Duration = Duration::from_secs(5);
+const GRACE_TIME: Duration = Duration::from_millis(1000);
+// Time kept on the clock for the answer to reach the interface, and moves the clock is shared
+// between when the interface doesn't say
+const MOVE_OVERHEAD: Duration = Duration::from_millis(50);
+const MOVES_TO_GO: u32 = 30;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum UciError {
+    Spawn(String),
+    Exited,
+    Timeout(&'static str),
+    IllegalMove(String),
+}
+
+impl fmt::Display for UciError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            UciError::Spawn(error) => write!(f, "Could not start the engine: {}", error),
+            UciError::Exited => write!(f, "The engine stopped running"),
+            UciError::Timeout(command) => {
+                write!(f, "The engine didn't answer \"{}\" in time", command)
+            }
+            UciError::IllegalMove(notation) => {
+                write!(f, "The engine played the illegal move {}", notation)
+            }
+        }
+    }
+}
+
+impl Error for UciError {}
+
+// Coordinate notation of a move for UCI engines: the king castles two cells away, or onto its
+// rook when the engine plays Chess960
+pub fn uci_notation(piece_move: &Move, chess960: bool) -> String {
+    match piece_move.kind {
+        MoveKind::Castling(side) if !chess960 => format!(
+            "{}{}",
+            coordinates_to_notation(piece_move.from.coordinates()),
+            coordinates_to_notation([piece_move.to.y, side.king_destination_x()])
+        ),
+        _ => piece_move.to_string(),
+    }
+}
+
+// Legal move of the player to play written in UCI coordinate notation
+pub fn parse_uci_move(board: &Board, notation: &str, chess960: bool) -> Option<Move> {
+    board
+        .legal_moves()
+        .into_iter()
+        .find(|legal_move| uci_notation(legal_move, chess960) == notation)
+}
+
+// The "position" command giving the starting position and the moves played since
+pub fn position_command(board: &Board) -> String {
+    let mut command = format!("position fen {}", board.starting_board().to_fen());
+    if !board.moves_history.is_empty() {
+        command += " moves";
+        for piece_move in &board.moves_history {
+            command += " ";
+            command += &uci_notation(piece_move, board.is_chess960());
+        }
+    }
+    command
+}
+
+// Depth, nodes, score and principal variation of an "info" line, the other fields are skipped
+fn read_info(line: &str, board: &Board, result: &mut SearchResult) {
+    let mut tokens = line.split_whitespace().skip(1);
+    while let Some(token) = tokens.next() {
+        match token {
+            "depth" => result.depth = tokens.next().and_then(|n| n.parse().ok()).unwrap_or(0),
+            "nodes" => result.nodes = tokens.next().and_then(|n| n.parse().ok()).unwrap_or(0),
+            "score" => match (
+                tokens.next(),
+                tokens.next().and_then(|n| n.parse::<i32>().ok()),
+            ) {
+                (Some("cp"), Some(score)) => result.score = score,
+                (Some("mate"), Some(moves)) if moves > 0 => result.score = MATE - (2 * moves - 1),
+                (Some("mate"), Some(moves)) => result.score = -MATE - 2 * moves,
+                _ => {}
+            },
+            "pv" => {
+                let mut position = board.clone();
+                result.principal_variation.clear();
+                for notation in tokens.by_ref() {
+                    let Some(piece_move) = parse_uci_move(&position, notation, board.is_chess960())
+                    else {
+                        break;
+                    };
+                    position.make_move(&piece_move);
+                    result.principal_variation.push(piece_move);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// External engine process driven over its standard input and output
+#[derive(Debug)]
+pub struct UciEngine {
+    pub name: String,
+    child: Child,
+    stdin: ChildStdin,
+    lines: mpsc::Receiver<String>,
+    // Value of the UCI_Chess960 option the engine was given, false until it is set
+    chess960: bool,
+    // Starting key and number of moves of the latest position searched, to tell a new game apart
+    game: Option<(u64, usize)>,
+}
+
+impl UciEngine {
+    // Start the engine and go through the "uci" and "isready" handshake
+    pub fn start(path: &str) -> Result<Self, UciError> {
+        Self::start_with_args(path, &[])
+    }
+
+    // Same as start, the engine is given command line arguments
+    pub fn start_with_args(path: &str, args: &[&str]) -> Result<Self, UciError> {
+        let mut child = Command::new(path)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|error| UciError::Spawn(format!("{}: {}", path, error)))?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(UciError::Spawn(path.to_string()));
+        };
+
+        // The lines are read on their own thread so that waiting for them can time out
+        let (sender, lines) = mpsc::channel();
+        thread::spawn(move || {
+            for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut engine = Self {
+            name: path.to_string(),
+            child,
+            stdin,
+            lines,
+            chess960: false,
+            game: None,
+        };
+        engine.send("uci")?;
+        let deadline = Instant::now() + ANSWER_TIME;
+        loop {
+            let line = engine
+                .read_line(deadline)?
+                .ok_or(UciError::Timeout("uci"))?;
+            if let Some(name) = line.strip_prefix("id name ") {
+                engine.name = name.trim().to_string();
+            } else if line.trim() == "uciok" {
+                break;
+            }
+        }
+        engine.is_ready()?;
+        Ok(engine)
+    }
+
+    fn send(&mut self, command: &str) -> Result<(), UciError> {
+        writeln!(self.stdin, "{}", command)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|_| UciError::Exited)
+    }
+
+    // Next line of the engine, none once the deadline is over
+    fn read_line(&mut self, deadline: Instant) -> Result<Option<String>, UciError> {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+        match self.lines.recv_timeout(timeout) {
+            Ok(line) => Ok(Some(line)),
+            Err(mpsc::RecvTimeoutError::Timeout) => Ok(None),
+            Err(mpsc::RecvTimeoutError::Disconnected) => Err(UciError::Exited),
+        }
+    }
+
+    pub fn is_ready(&mut self) -> Result<(), UciError> {
+        self.send("isready")?;
+        let deadline = Instant::now() + ANSWER_TIME;
+        loop {
+            match self.read_line(deadline)? {
+                Some(line) if line.trim() == "readyok" => return Ok(()),
+                Some(_) => {}
+                None => return Err(UciError::Timeout("isready")),
+            }
+        }
+    }
+
+    // Let the engine think on the position for the given time. It is told to stop once the time
+    // is over, and given up on if it still doesn't answer
+    pub fn best_move(&mut self, board: &Board, time: Duration) -> Result<SearchResult, UciError> {
+        // A game from another start, with fewer moves or other rules is a new game for the engine
+        let chess960 = board.is_chess960();
+        let starting_key = board.starting_position.zobrist_key;
+        let move_count = board.moves_history.len();
+        let is_new_game = chess960 != self.chess960
+            || self
+                .game
+                .is_none_or(|(key, moves)| key != starting_key || move_count < moves);
+        if chess960 != self.chess960 {
+            self.send(&format!("setoption name UCI_Chess960 value {}", chess960))?;
+            self.chess960 = chess960;
+        }
+        if is_new_game {
+            self.send("ucinewgame")?;
+            self.is_ready()?;
+        }
+        self.game = Some((starting_key, move_count));
+        self.send(&position_command(board))?;
+        self.send(&format!("go movetime {}", time.as_millis()))?;
+
+        let mut result = SearchResult {
+            best_move: None,
+            score: 0,
+            depth: 0,
+            principal_variation: vec![],
+            nodes: 0,
+        };
+        let mut deadline = Instant::now() + time + GRACE_TIME;
+        let mut is_stopped = false;
+        loop {
+            match self.read_line(deadline)? {
+                Some(line) if line.starts_with("info ") => read_info(&line, board, &mut result),
+                Some(line) if line.starts_with("bestmove") => {
+                    let notation = line.split_whitespace().nth(1).unwrap_or("(none)");
+                    if notation != "(none)" {
+                        let best_move = parse_uci_move(board, notation, board.is_chess960())
+                            .ok_or_else(|| UciError::IllegalMove(notation.to_string()))?;
+                        if result.principal_variation.first() != Some(&best_move) {
+                            result.principal_variation = vec![best_move];
+                        }
+                        result.best_move = Some(best_move);
+                    }
+                    return Ok(result);
+                }
+                Some(_) => {}
+                None if !is_stopped => {
+                    self.send("stop")?;
+                    is_stopped = true;
+                    deadline = Instant::now() + GRACE_TIME;
+                }
+                None => return Err(UciError::Timeout("go")),
+            }
+        }
+    }
+}
+
+impl Drop for UciEngine {
+    fn drop(&mut self) {
+        let _ = self.send("quit");
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+// Time to think on a move with the time left on the clock and the increment
+fn time_for_move(time_left: Duration, increment: Duration, moves_to_go: Option<u32>) -> Duration {
+    let share = time_left / moves_to_go.unwrap_or(MOVES_TO_GO).max(1) + increment / 2;
+    share.min(time_left.saturating_sub(MOVE_OVERHEAD))
+}
+
+// The "info" line sent once a depth is searched
+fn info_line(result: &SearchResult, elapsed: Duration, chess960: bool) -> String {
+    let score = match result.mate_in() {
+        Some(moves) => format!("mate {}", moves),
+        None => format!("cp {}", result.score),
+    };
+    let milliseconds = elapsed.as_millis().max(1);
+    let mut line = format!(
+        "info depth {} score {} nodes {} nps {} time {}",
+        result.depth,
+        score,
+        result.nodes,
+        result.nodes as u128 * 1000 / milliseconds,
+        elapsed.as_millis()
+    );
+    if !result.principal_variation.is_empty() {
+        line += " pv";
+        for piece_move in &result.principal_variation {
+            line += " ";
+            line += &uci_notation(piece_move, chess960);
+        }
+    }
+    line
+}
+
+fn write_line<W: Write>(output: &Mutex<W>, line: &str) {
+    if let Ok(mut output) = output.lock() {
+        let _ = writeln!(output, "{}", line).and_then(|_| output.flush());
+    }
+}
+
+// This crate played as a UCI engine: commands are read line by line, the search runs on its own
+// thread so that "stop" and "isready" are answered while it thinks
+pub struct UciServer<W: Write + Send + 'static> {
+    board: Board,
+    chess960: bool,
+    output: Arc<Mutex<W>>,
+    stop: Arc<AtomicBool>,
+    search: Option<thread::JoinHandle<()>>,
+}
+
+impl<W: Write + Send + 'static> UciServer<W> {
+    pub fn new(output: W) -> Self {
+        Self {
+            board: Board::standard(),
+            chess960: false,
+            output: Arc::new(Mutex::new(output)),
+            stop: Arc::new(AtomicBool::new(false)),
+            search: None,
+        }
+    }
+
+    // Answer the commands until "quit" or the end of the input
+    pub fn run(&mut self, input: impl BufRead) {
+        for line in input.lines().map_while(Result::ok) {
+            if !self.handle(&line) {
+                break;
+            }
+        }
+        self.stop_search();
+    }
+
+    // Answer a command, false once told to quit. Unknown commands are skipped as the protocol
+    // asks
+    pub fn handle(&mut self, line: &str) -> bool {
+        let mut tokens = line.split_whitespace();
+        match tokens.next() {
+            Some("uci") => {
+                self.send(&format!(
+                    "id name {} {}",
+                    env!("CARGO_PKG_NAME"),
+                    env!("CARGO_PKG_VERSION")
+                ));
+                self.send(&format!("id author {}", env!("CARGO_PKG_AUTHORS")));
+                self.send("option name UCI_Chess960 type check default false");
+                self.send("uciok");
+            }
+            Some("isready") => self.send("readyok"),
+            Some("setoption") => {
+                let option: Vec<&str> = tokens.collect();
+                if let ["name", "UCI_Chess960", "value", value] = option.as_slice() {
+                    self.chess960 = *value == "true";
+                }
+            }
+            Some("ucinewgame") => {
+                self.stop_search();
+                self.board = Board::standard();
+            }
+            Some("position") => {
+                self.stop_search();
+                self.set_position(tokens.collect());
+            }
+            Some("go") => {
+                self.stop_search();
+                self.go(tokens.collect());
+            }
+            Some("stop") | Some("ponderhit") => self.stop_search(),
+            Some("quit") => return false,
+            _ => {}
+        }
+        true
+    }
+
+    fn send(&self, line: &str) {
+        write_line(&self.output, line);
+    }
+
+    // "position startpos|fen <fen> [moves <move>...]", the position is left as it was when the
+    // starting position is wrong, and the moves stop at the first illegal one
+    fn set_position(&mut self, tokens: Vec<&str>) {
+        let moves_index = tokens
+            .iter()
+            .position(|token| *token == "moves")
+            .unwrap_or(tokens.len());
+        let mut board = match tokens.first() {
+            Some(&"startpos") => Board::standard(),
+            Some(&"fen") => match Board::from_fen(&tokens[1..moves_index].join(" ")) {
+                Ok(board) => board,
+                Err(error) => return self.send(&format!("info string {}", error)),
+            },
+            _ => return,
+        };
+        for notation in tokens.iter().skip(moves_index + 1) {
+            let played = parse_uci_move(&board, notation, self.chess960).map(|piece_move| {
+                board.try_move(
+                    piece_move.from.coordinates(),
+                    piece_move.to.coordinates(),
+                    piece_move.promotion,
+                )
+            });
+            if !matches!(played, Some(Ok(_))) {
+                self.send(&format!("info string illegal move {}", notation));
+                break;
+            }
+        }
+        self.board = board;
+    }
+
+    // "go" with a depth, a time to think or the clocks, the search goes on until "stop" without
+    // any of them. In infinite and ponder mode the best move waits for "stop" even once the
+    // search is over
+    fn go(&mut self, tokens: Vec<&str>) {
+        let infinite = tokens.contains(&"infinite") || tokens.contains(&"ponder");
+        let value = |name: &str| {
+            tokens
+                .iter()
+                .position(|token| *token == name)
+                .and_then(|index| tokens.get(index + 1))
+                .and_then(|value| value.parse::<u64>().ok())
+        };
+        let (time_left, increment) = match self.board.player_turn {
+            PieceColor::White => (value("wtime"), value("winc")),
+            PieceColor::Black => (value("btime"), value("binc")),
+        };
+        let time = match (value("movetime"), time_left) {
+            (Some(movetime), _) => Duration::from_millis(movetime),
+            (None, Some(time_left)) => time_for_move(
+                Duration::from_millis(time_left),
+                Duration::from_millis(increment.unwrap_or(0)),
+                value("movestogo").map(|moves| moves as u32),
+            ),
+            (None, None) => Duration::MAX,
+        };
+        let limits = SearchLimits {
+            max_depth: value("depth")
+                .map_or(SearchLimits::default().max_depth, |depth| depth as u32),
+            time,
+        };
+
+        let board = self.board.clone();
+        let chess960 = self.chess960;
+        let output = self.output.clone();
+        let stop = self.stop.clone();
+        stop.store(false, Ordering::Relaxed);
+        self.search = Some(thread::spawn(move || {
+            let started = Instant::now();
+            let result = search_with_reports(&board, limits, &stop, |result| {
+                write_line(&output, &info_line(result, started.elapsed(), chess960))
+            });
+            while infinite && !stop.load(Ordering::Relaxed) {
+                thread::sleep(Duration::from_millis(1));
+            }
+            let best_move = result.best_move.map_or("(none)".to_string(), |best_move| {
+                uci_notation(&best_move, chess960)
+            });
+            write_line(&output, &format!("bestmove {}", best_move));
+        }));
+    }
+
+    // Stop the search going on, its best move is sent before this returns
+    fn stop_search(&mut self) {
+        if let Some(search) = self.search.take() {
+            self.stop.store(true, Ordering::Relaxed);
+            let _ = search.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs,
+        io::Write,
+        path::PathBuf,
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    use crate::{
+        board::Board,
+        engine::MATE,
+        moves::MoveKind,
+        uci::{
+            parse_uci_move, position_command, time_for_move, uci_notation, UciEngine, UciError,
+            UciServer,
+        },
+    };
+
+    // Output of the server kept to be read by the test
+    #[derive(Clone, Default)]
+    struct SharedOutput(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedOutput {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedOutput {
+        // Lines written since the last call
+        fn take_lines(&self) -> Vec<String> {
+            let output = std::mem::take(&mut *self.0.lock().unwrap());
+            String::from_utf8(output)
+                .unwrap()
+                .lines()
+                .map(str::to_string)
+                .collect()
+        }
+    }
+
+    // Send the commands then wait for the search to be over
+    fn answers(
+        server: &mut UciServer<SharedOutput>,
+        output: &SharedOutput,
+        commands: &[&str],
+    ) -> Vec<String> {
+        for command in commands {
+            assert!(server.handle(command));
+        }
+        if let Some(search) = server.search.take() {
+            search.join().unwrap();
+        }
+        output.take_lines()
+    }
+
+    // Scripted engine speaking UCI, its name and its answer to "go" are given as arguments. The
+    // answer can read the value of UCI_Chess960 in $chess960 and the number of new games in
+    // $games. The script is removed once the test is over
+    #[cfg(unix)]
+    struct MockEngine(PathBuf);
+
+    #[cfg(unix)]
+    impl MockEngine {
+        fn new() -> Self {
+            use std::os::unix::fs::PermissionsExt;
+
+            let path = std::env::temp_dir().join(format!("chess_mock_{}", std::process::id()));
+            let script = "#!/bin/sh\n\
+                          chess960=false\n\
+                          games=0\n\
+                          while read -r command rest; do\n\
+                            case \"$command\" in\n\
+                              uci) echo \"id name Mock $1\"; echo \"id author nobody\"; echo uciok ;;\n\
+                              isready) echo readyok ;;\n\
+                              setoption) chess960=${rest##* } ;;\n\
+                              ucinewgame) games=$((games + 1)) ;;\n\
+                              go) eval \"$2\" ;;\n\
+                              quit) exit 0 ;;\n\
+                            esac\n\
+                          done\n";
+            fs::write(&path, script).unwrap();
+            fs::set_permissions(&path, fs::Permissions::from_mode(0o755)).unwrap();
+            Self(path)
+        }
+
+        fn start(&self, name: &str, go_answer: &str) -> Result<UciEngine, UciError> {
+            UciEngine::start_with_args(self.0.to_str().unwrap(), &[name, go_answer])
+        }
+    }
+
+    #[cfg(unix)]
+    impl Drop for MockEngine {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn notation() {
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+        let castling = parse_uci_move(&board, "e1g1", false).unwrap();
+        assert_eq!(
+            castling.kind,
+            MoveKind::Castling(crate::moves::CastlingSide::King)
+        );
+        assert_eq!(uci_notation(&castling, false), "e1g1");
+        assert_eq!(uci_notation(&castling, true), "e1h1");
+        assert_eq!(parse_uci_move(&board, "e1h1", false), None);
+        assert_eq!(parse_uci_move(&board, "e1h1", true), Some(castling));
+
+        let mut board = Board::from_fen("8/P6k/8/8/8/8/8/K7 w - - 0 1").unwrap();
+        let promotion = parse_uci_move(&board, "a7a8n", false).unwrap();
+        assert_eq!(uci_notation(&promotion, false), "a7a8n");
+        assert_eq!(parse_uci_move(&board, "a7a8", false), None);
+
+        board
+            .try_move([1, 0], [0, 0], Some(crate::pieces::PieceType::Rook))
+            .unwrap();
+        assert_eq!(
+            position_command(&board),
+            "position fen 8/P6k/8/8/8/8/8/K7 w - - 0 1 moves a7a8r"
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn mock_engine_games() {
+        let mock = MockEngine::new();
+        let mut engine = mock
+            .start(
+                "play",
+                "echo \"info depth 3 score mate 2 nodes 42 pv e2e4 e7e5\"; echo \"bestmove e2e4\"",
+            )
+            .unwrap();
+        assert_eq!(engine.name, "Mock play");
+
+        let board = Board::standard();
+        let result = engine.best_move(&board, Duration::from_millis(50)).unwrap();
+        assert_eq!(result.best_move.unwrap().to_string(), "e2e4");
+        assert_eq!(result.depth, 3);
+        assert_eq!(result.nodes, 42);
+        assert_eq!(result.score, MATE - 3);
+        assert_eq!(result.principal_variation.len(), 2);
+        drop(engine);
+
+        // An illegal move, a crash and an engine that never answers are errors
+        let mut engine = mock.start("illegal", "echo \"bestmove e2e5\"").unwrap();
+        assert_eq!(
+            engine.best_move(&board, Duration::from_millis(50)),
+            Err(UciError::IllegalMove("e2e5".to_string()))
+        );
+
+        let mut engine = mock.start("crash", "exit 1").unwrap();
+        assert_eq!(
+            engine.best_move(&board, Duration::from_millis(50)),
+            Err(UciError::Exited)
+        );
+
+        let mut engine = mock.start("silent", ":").unwrap();
+        assert_eq!(
+            engine.best_move(&board, Duration::from_millis(10)),
+            Err(UciError::Timeout("go"))
+        );
+
+        // The engine castles the way its UCI_Chess960 option says, and counts the new games
+        let mut engine = mock
+            .start(
+                "castling",
+                "castling=e1g1; [ \"$chess960\" = true ] && castling=e1h1; \
+                 echo \"info depth $games\"; echo \"bestmove $castling\"",
+            )
+            .unwrap();
+        let standard = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+        let chess960 = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w HAha - 0 1").unwrap();
+        for (board, games) in [
+            (&standard, 1),
+            (&standard, 1),
+            (&chess960, 2),
+            (&standard, 3),
+        ] {
+            let result = engine.best_move(board, Duration::from_millis(50)).unwrap();
+            assert_eq!(
+                result.best_move.unwrap().kind,
+                MoveKind::Castling(crate::moves::CastlingSide::King)
+            );
+            assert_eq!(result.depth, games);
+        }
+
+        assert!(matches!(
+            UciEngine::start("/nonexistent/engine"),
+            Err(UciError::Spawn(_))
+        ));
+    }
+
+    #[test]
+    fn server() {
+        let output = SharedOutput::default();
+        let mut server = UciServer::new(output.clone());
+        let lines = answers(&mut server, &output, &["uci", "isready"]);
+        assert!(lines[0].starts_with("id name"));
+        assert_eq!(lines[lines.len() - 2..], ["uciok", "readyok"]);
+
+        let lines = answers(
+            &mut server,
+            &output,
+            &[
+                "ucinewgame",
+                "position startpos moves e2e4 e7e5",
+                "go depth 2",
+            ],
+        );
+        assert!(lines[0].starts_with("info depth 1 score cp "));
+        assert!(lines[1].starts_with("info depth 2 "));
+        let best_move = lines[2].strip_prefix("bestmove ").unwrap();
+        assert!(parse_uci_move(&server.board, best_move, false).is_some());
+
+        let lines = answers(
+            &mut server,
+            &output,
+            &[
+                "position fen 6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1",
+                "go wtime 60000 btime 60000",
+            ],
+        );
+        let last_info = &lines[lines.len() - 2];
+        assert!(last_info.contains(" score mate 1 "));
+        assert!(last_info.ends_with(" pv a1a8"));
+        assert_eq!(lines.last().unwrap(), "bestmove a1a8");
+
+        // An infinite search ends with its best move once stopped
+        for command in ["position startpos", "go infinite"] {
+            server.handle(command);
+        }
+        std::thread::sleep(Duration::from_millis(50));
+        server.handle("stop");
+        assert!(output.take_lines().last().unwrap().starts_with("bestmove "));
+
+        // The mate is found at once, the best move still waits for "stop"
+        for command in [
+            "position fen 6k1/5ppp/8/8/8/8/5PPP/R5K1 w - - 0 1",
+            "go infinite",
+        ] {
+            server.handle(command);
+        }
+        std::thread::sleep(Duration::from_millis(200));
+        assert!(!output
+            .take_lines()
+            .iter()
+            .any(|line| line.starts_with("bestmove")));
+        server.handle("stop");
+        assert_eq!(output.take_lines(), ["bestmove a1a8"]);
+
+        // Castling onto the rook in Chess960, and moves past an illegal one are dropped
+        let lines = answers(
+            &mut server,
+            &output,
+            &[
+                "setoption name UCI_Chess960 value true",
+                "position fen 4k3/8/8/8/8/8/8/RK5R w HA - 0 1 moves b1a1 e8e7 e7e6",
+            ],
+        );
+        assert_eq!(lines, ["info string illegal move e7e6"]);
+        assert_eq!(server.board.to_fen(), "8/4k3/8/8/8/8/8/2KR3R w - - 2 2");
+        assert!(!server.handle("quit"));
+    }
+
+    #[test]
+    fn time_management() {
+        let minute = Duration::from_secs(60);
+        assert_eq!(
+            time_for_move(minute, Duration::ZERO, None),
+            Duration::from_secs(2)
+        );
+        assert_eq!(
+            time_for_move(minute, Duration::from_secs(2), Some(10)),
+            Duration::from_secs(7)
+        );
+        assert_eq!(
+            time_for_move(Duration::from_millis(100), Duration::from_secs(1), None),
+            Duration::from_millis(50)
+        );
+    }
+}
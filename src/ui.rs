@@ -10,8 +10,8 @@ use ratatui::{
 use crate::{
     app::App,
     constants::WHITE,
-    pieces::{bishop::Bishop, knight::Knight, queen::Queen, rook::Rook, PieceColor},
-    utils::get_opposite_color,
+    pieces::{bishop::Bishop, knight::Knight, queen::Queen, rook::Rook},
+    status::GameStatus,
 };
 
 /// Renders the user interface widgets.
@@ -51,12 +51,29 @@ pub fn render(app: &mut App, frame: &mut Frame) {
     frame.render_widget(board_block.clone(), main_layout_vertical[1]);
 
     // We make the inside of the board
-    app.board
-        .board_render(board_block.inner(main_layout_vertical[1]), frame);
+    app.board.board_render(
+        board_block.inner(main_layout_vertical[1]),
+        frame,
+        app.current_hint().and_then(|hint| hint.best_move),
+    );
+
+    if app.show_evaluation {
+        render_evaluation_bar(frame, app.board.evaluate(), main_layout_vertical[2]);
+    }
 
     // We make the inside of the board
-    app.board
-        .history_render(board_block.inner(main_layout_vertical[3]), frame);
+    app.board.history_render(
+        board_block.inner(main_layout_vertical[3]),
+        frame,
+        app.current_hint().map(|hint| hint.explanation(&app.board)),
+    );
+
+    if let Some(message) = &app.message {
+        frame.render_widget(
+            Paragraph::new(message.as_str()).alignment(Alignment::Center),
+            main_layout_horizontal[2],
+        );
+    }
 
     if app.show_popup {
         render_help_popup(frame)
@@ -66,22 +83,49 @@ pub fn render(app: &mut App, frame: &mut Frame) {
         render_promotion_popup(frame, app)
     }
 
-    if app.board.is_pat {
-        render_end_popup(frame, "That's a draw".to_string())
+    match app.board.status() {
+        GameStatus::Ongoing => {}
+        status => render_end_popup(frame, status.to_string()),
     }
 
-    if app.board.is_checkmate {
-        let victorious_player = get_opposite_color(app.board.player_turn);
-
-        let string_color = match victorious_player {
-            PieceColor::White => "White",
-            PieceColor::Black => "Black",
-        };
-
-        render_end_popup(frame, format!("{} Won !!!", string_color))
+    if let Some(error) = &app.error {
+        render_error_popup(frame, error)
     }
 }
 
+/// Renders the evaluation as a bar as high as the board, White's share from the bottom and the
+/// score in pawns in the middle
+pub fn render_evaluation_bar(frame: &mut Frame, evaluation: i32, area: Rect) {
+    // The board keeps the same margins above and below its 8 rows
+    let height = area.height / 8 * 8;
+    let area = Rect::new(
+        area.x + area.width / 4,
+        area.y + (area.height - height) / 2,
+        area.width / 2,
+        height,
+    );
+    // Winning chances of White from the score, half the bar for an even position
+    let white_share = 1.0 / (1.0 + 10f64.powf(-f64::from(evaluation) / 400.0));
+    let white_height = (white_share * f64::from(height)).round() as u16;
+
+    let black_area = Rect::new(area.x, area.y, area.width, height - white_height);
+    let white_area = Rect::new(area.x, area.y + height - white_height, area.width, white_height);
+    frame.render_widget(
+        Block::default().style(Style::default().bg(Color::Rgb(69, 54, 49))),
+        black_area,
+    );
+    frame.render_widget(Block::default().style(Style::default().bg(WHITE)), white_area);
+
+    let score = format!("{:+.1}", f64::from(evaluation) / 100.0);
+    let score_area = Rect::new(area.x, area.y + height / 2, area.width, 1);
+    frame.render_widget(
+        Paragraph::new(score)
+            .alignment(Alignment::Center)
+            .style(Style::default().fg(Color::Black).bg(Color::Gray)),
+        score_area,
+    );
+}
+
 pub fn render_help_popup(frame: &mut Frame) {
     let block = Block::default()
         .title("Help menu")
@@ -109,6 +153,20 @@ pub fn render_help_popup(frame: &mut Frame) {
             " the available cells for this piece. You can then hit SPACE_BAR again to move on that square".into(),
         ]),
         Line::from(""),
+        Line::from("s: Press s to save the game as a PGN file"),
+        Line::from(""),
+        Line::from("f: Press f before the first move or once the game is over to start a Chess960 game"),
+        Line::from(""),
+        Line::from("a: Press a before the first move or once the game is over to play against the computer as White, as Black or against another player"),
+        Line::from(""),
+        Line::from("i: Press i for a hint, the cells of the best move turn yellow and the side panel shows its score and line"),
+        Line::from(""),
+        Line::from("e: Press e to show or hide the evaluation bar, White's advantage fills it from the bottom"),
+        Line::from(""),
+        Line::from("u / U: Press u to take back the latest move and U to play it again"),
+        Line::from(""),
+        Line::from("d: Press d to claim a draw once fifty moves each were played without capture or pawn move"),
+        Line::from(""),
         Line::from("q: Press q to quit "),
         Line::from(""),
         Line::from(vec![
@@ -180,6 +238,34 @@ pub fn render_end_popup(frame: &mut Frame, sentence: String) {
     frame.render_widget(paragraph, area);
 }
 
+pub fn render_error_popup(frame: &mut Frame, error: &str) {
+    let block = Block::default()
+        .title("Engine error")
+        .borders(Borders::ALL)
+        .border_type(BorderType::Rounded)
+        .padding(Padding::horizontal(1))
+        .border_style(Style::default().fg(WHITE));
+    let area = centered_rect(40, 40, frame.size());
+
+    let text = vec![
+        Line::from(error.to_string()).alignment(Alignment::Center),
+        Line::from(""),
+        Line::from(""),
+        Line::from("The game goes on between two players").alignment(Alignment::Center),
+        Line::from(""),
+        Line::from("Press any key to close this popup").alignment(Alignment::Center),
+    ];
+
+    let paragraph = Paragraph::new(text)
+        .block(block.clone())
+        .alignment(Alignment::Left)
+        .wrap(Wrap { trim: true });
+
+    frame.render_widget(Clear, area); //this clears out the background
+    frame.render_widget(block, area);
+    frame.render_widget(paragraph, area);
+}
+
 pub fn render_promotion_popup(frame: &mut Frame, app: &App) {
     let block = Block::default()
         .title("Pawn promotion")
@@ -264,4 +350,4 @@ pub fn render_promotion_popup(frame: &mut Frame, app: &App) {
             Color::Reset // Set to the default background color when the condition is false
         }));
     frame.render_widget(knight_p, inner_popup_layout_horizontal[3]);
-}
\ No newline at end of file
+}
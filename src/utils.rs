@@ -1,6 +1,7 @@
 use crate::{
-  board::Board,
+  bitboard::Bitboards,
   constants::UNDEFINED_POSITION,
+  moves::Move,
   pieces::{PieceColor, PieceType},
 };
 
@@ -63,35 +64,6 @@ pub fn is_vec_in_array(array: Vec<Vec<i8>>, element: [i8; 2]) -> bool {
   false
 }
 
-// We get all the cells that are getting put in 'check'
-pub fn get_all_protected_cells(
-  board: [[Option<(PieceType, PieceColor)>; 8]; 8],
-  player_turn: PieceColor,
-  move_history: Vec<(Option<PieceType>, String)>,
-) -> Vec<Vec<i8>> {
-  let mut check_cells: Vec<Vec<i8>> = vec![];
-  for i in 0..8i8 {
-      for j in 0..8i8 {
-          if get_piece_color(board, [i, j]) == Some(player_turn) {
-              continue;
-          }
-          // get the current cell piece color and type protecting positions
-          if let Some(piece_color) = get_piece_color(board, [i, j]) {
-              if let Some(piece_type) = get_piece_type(board, [i, j]) {
-                  check_cells.extend(PieceType::protected_positions(
-                      [i, j],
-                      piece_type,
-                      piece_color,
-                      board,
-                      move_history.clone(),
-                  ));
-              }
-          }
-      }
-  }
-  check_cells
-}
-
 pub fn col_to_letter(col: i8) -> String {
   match col {
       0 => "a".to_string(),
@@ -106,20 +78,19 @@ pub fn col_to_letter(col: i8) -> String {
   }
 }
 
-pub fn convert_position_into_notation(position: String) -> String {
-  let mut result: String = "".to_string();
-  let from_y = get_int_from_char(position.chars().next());
-  let from_x = get_int_from_char(position.chars().nth(1));
-  let to_y = get_int_from_char(position.chars().nth(2));
-  let to_x = get_int_from_char(position.chars().nth(3));
-
-  result += &col_to_letter(from_x);
-  result += &format!("{}", (8 - from_y) % 8).to_string();
-  result += "-";
-  result += &col_to_letter(to_x);
-  result += &format!("{}", (8 - to_y) % 8).to_string();
+// [y, x] coordinates to algebraic notation, [7, 4] is "e1"
+pub fn coordinates_to_notation(coordinates: [i8; 2]) -> String {
+  format!("{}{}", col_to_letter(coordinates[1]), 8 - coordinates[0])
+}
 
-  result
+// Algebraic notation to [y, x] coordinates, "e1" is [7, 4]
+pub fn notation_to_coordinates(notation: &str) -> Option<[i8; 2]> {
+  let mut chars = notation.chars();
+  let (file, rank) = (chars.next()?, chars.next()?);
+  if chars.next().is_some() || !('a'..='h').contains(&file) || !('1'..='8').contains(&rank) {
+      return None;
+  }
+  Some([8 - (rank as u8 - b'0') as i8, (file as u8 - b'a') as i8])
 }
 
 pub fn get_player_turn_in_modulo(color: PieceColor) -> usize {
@@ -129,38 +100,42 @@ pub fn get_player_turn_in_modulo(color: PieceColor) -> usize {
   }
 }
 
-pub fn get_int_from_char(ch: Option<char>) -> i8 {
-  match ch {
-      Some(ch) => ch.to_digit(10).unwrap() as i8,
-      _ => -1,
-  }
+pub fn get_latest_move(move_history: &[Move]) -> Option<Move> {
+  move_history.last().copied()
 }
 
-pub fn get_latest_move(
-  move_history: &Vec<(Option<PieceType>, String)>,
-) -> (Option<PieceType>, String) {
-  if !move_history.is_empty() {
-      return move_history[move_history.len() - 1].clone();
-  }
-  (None, "0000".to_string())
+pub fn did_piece_already_move(move_history: &[Move], original_piece: (PieceType, [i8; 2])) -> bool {
+  // Here there is an entry with the same piece type and the same original position, meaning it moved at some point
+  move_history.iter().any(|entry| {
+      entry.piece_type == original_piece.0 && entry.from.coordinates() == original_piece.1
+  })
 }
 
-pub fn did_piece_already_move(
-  move_history: &Vec<(Option<PieceType>, String)>,
-  original_piece: (Option<PieceType>, [i8; 2]),
-) -> bool {
-  for entry in move_history {
-      let position = entry.1.clone();
-      let from_y = get_int_from_char(position.chars().next());
-      let from_x = get_int_from_char(position.chars().nth(1));
-      // Here there is an entry with the same piece type and the same original position, meaning it moved at some point
-      if entry.0 == original_piece.0 && [from_y, from_x] == original_piece.1 {
-          return true;
+// Neither side can checkmate: bare kings, a single minor piece, or bishops all on the same cell color
+pub fn is_insufficient_material(board: [[Option<(PieceType, PieceColor)>; 8]; 8]) -> bool {
+  let mut knights = 0;
+  let mut bishop_cell_colors: Vec<i8> = vec![];
+
+  for i in 0..8i8 {
+      for j in 0..8i8 {
+          match get_piece_type(board, [i, j]) {
+              Some(PieceType::King) | None => {}
+              Some(PieceType::Knight) => knights += 1,
+              Some(PieceType::Bishop) => bishop_cell_colors.push((i + j) % 2),
+              Some(PieceType::Pawn) | Some(PieceType::Rook) | Some(PieceType::Queen) => return false,
+          }
       }
   }
-  false
+
+  match (knights, bishop_cell_colors.as_slice()) {
+      (0, []) | (1, []) => true,
+      (0, [first, others @ ..]) => others.iter().all(|cell_color| cell_color == first),
+      _ => false,
+  }
 }
+
 // Method returning the coordinates of the king of a certain color
+#[allow(clippy::single_match, clippy::collapsible_match)]
 pub fn get_king_coordinates(
   board: [[Option<(PieceType, PieceColor)>; 8]; 8],
   player_turn: PieceColor,
@@ -180,57 +155,12 @@ pub fn get_king_coordinates(
   [UNDEFINED_POSITION, UNDEFINED_POSITION]
 }
 
-// Is getting checked
+// Is getting checked, the attacks are found on bitboards
 pub fn is_getting_checked(
   board: [[Option<(PieceType, PieceColor)>; 8]; 8],
   player_turn: PieceColor,
-  move_history: Vec<(Option<PieceType>, String)>,
 ) -> bool {
-  let coordinates = get_king_coordinates(board, player_turn);
-
-  let checked_cells = get_all_protected_cells(board, player_turn, move_history);
-
-  for position in checked_cells {
-      if position == coordinates {
-          return true;
-      }
-  }
-  false
-}
-
-pub fn impossible_positions_king_checked(
-  original_coordinates: [i8; 2],
-  positions: Vec<Vec<i8>>,
-  board: [[Option<(PieceType, PieceColor)>; 8]; 8],
-  color: PieceColor,
-  move_history: Vec<(Option<PieceType>, String)>,
-) -> Vec<Vec<i8>> {
-  let mut cleaned_position: Vec<Vec<i8>> = vec![];
-  for position in positions {
-      // We create a new board
-      let mut new_board = Board::new(board, color, move_history.clone());
-
-      // We simulate the move
-
-      Board::move_piece_on_the_board(
-          &mut new_board,
-          [
-              original_coordinates[0] as usize,
-              original_coordinates[1] as usize,
-          ],
-          [position[0] as usize, position[1] as usize],
-      );
-
-      // We check if the board is still checked with this move meaning it didn't resolve the problem
-      if !is_getting_checked(
-          new_board.board,
-          new_board.player_turn,
-          new_board.moves_history,
-      ) {
-          cleaned_position.push(position)
-      };
-  }
-  cleaned_position
+  Bitboards::from_board(&board).is_in_check(player_turn)
 }
 
 pub fn is_piece_opposite_king(piece: Option<(PieceType, PieceColor)>, color: PieceColor) -> bool {
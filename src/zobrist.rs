@@ -0,0 +1,178 @@
+use crate::{
+    bitboard::{color_index, piece_index},
+    board::Board,
+    moves::{CastlingRights, CastlingSide},
+    pieces::{PieceColor, PieceType},
+};
+
+// One random number per piece on each cell, then black to play, then each castling right for each
+// rook file, then each en passant file
+const PIECE_KEYS: usize = 0;
+const SIDE_KEY: usize = PIECE_KEYS + 2 * 6 * 64;
+const CASTLING_KEYS: usize = SIDE_KEY + 1;
+const EN_PASSANT_KEYS: usize = CASTLING_KEYS + 2 * 2 * 8;
+const KEY_COUNT: usize = EN_PASSANT_KEYS + 8;
+
+// SplitMix64 from a fixed seed, the keys are the same from one run to the next
+const fn random_keys() -> [u64; KEY_COUNT] {
+    let mut keys = [0; KEY_COUNT];
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut i = 0;
+    while i < KEY_COUNT {
+        state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        keys[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    keys
+}
+
+static KEYS: [u64; KEY_COUNT] = random_keys();
+
+pub fn piece_key(piece_type: PieceType, color: PieceColor, coordinates: [i8; 2]) -> u64 {
+    let square = (coordinates[0] * 8 + coordinates[1]) as usize;
+    KEYS[PIECE_KEYS + (color_index(color) * 6 + piece_index(piece_type)) * 64 + square]
+}
+
+pub fn side_key() -> u64 {
+    KEYS[SIDE_KEY]
+}
+
+// Every right left is hashed with the file of its rook, Chess960 rights included
+pub fn castling_key(castling_rights: CastlingRights) -> u64 {
+    let mut key = 0;
+    for color in [PieceColor::White, PieceColor::Black] {
+        for (side_index, side) in [CastlingSide::King, CastlingSide::Queen].iter().enumerate() {
+            if let Some(rook_x) = castling_rights.rook_x(color, *side) {
+                key ^= KEYS
+                    [CASTLING_KEYS + (color_index(color) * 2 + side_index) * 8 + rook_x as usize];
+            }
+        }
+    }
+    key
+}
+
+pub fn en_passant_key(file: i8) -> u64 {
+    KEYS[EN_PASSANT_KEYS + file as usize]
+}
+
+impl Board {
+    // Key of the position built from scratch, the one kept on the board is updated move by move
+    // and checked against this one in debug builds
+    pub fn compute_zobrist_key(&self) -> u64 {
+        let mut key = 0;
+        for (y, row) in self.board.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                if let Some((piece_type, piece_color)) = cell {
+                    key ^= piece_key(*piece_type, *piece_color, [y as i8, x as i8]);
+                }
+            }
+        }
+        if self.player_turn == PieceColor::Black {
+            key ^= side_key();
+        }
+        key ^ castling_key(self.castling_rights) ^ self.en_passant_zobrist_key()
+    }
+
+    // The en passant file only counts when a pawn can take, like for the repetition rule
+    pub(crate) fn en_passant_zobrist_key(&self) -> u64 {
+        self.en_passant_capture_target()
+            .map_or(0, |target| en_passant_key(target.x))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        board::Board,
+        fen::STARTING_POSITION_FEN,
+        zobrist::{KEYS, KEY_COUNT},
+    };
+
+    fn play(board: &mut Board, moves: &[([i8; 2], [i8; 2])]) {
+        for (from, to) in moves {
+            board.try_move(*from, *to, None).unwrap();
+        }
+    }
+
+    #[test]
+    fn random_keys() {
+        let mut keys = KEYS.to_vec();
+        keys.sort();
+        keys.dedup();
+        assert_eq!(keys.len(), KEY_COUNT);
+    }
+
+    #[test]
+    fn transpositions() {
+        let start = Board::standard();
+        assert_eq!(start.zobrist_key, start.compute_zobrist_key());
+        assert_eq!(
+            start.zobrist_key,
+            Board::from_fen(STARTING_POSITION_FEN).unwrap().zobrist_key
+        );
+
+        // The knights going back and forth give the starting position again
+        let mut board = Board::standard();
+        play(
+            &mut board,
+            &[
+                ([7, 6], [5, 5]),
+                ([0, 6], [2, 5]),
+                ([5, 5], [7, 6]),
+                ([2, 5], [0, 6]),
+            ],
+        );
+        assert_eq!(board.zobrist_key, start.zobrist_key);
+
+        // 1. e4 e5 2. Nf3 and 1. Nf3 e5 2. e4
+        let mut first = Board::standard();
+        play(
+            &mut first,
+            &[([6, 4], [4, 4]), ([1, 4], [3, 4]), ([7, 6], [5, 5])],
+        );
+        let mut second = Board::standard();
+        play(
+            &mut second,
+            &[([7, 6], [5, 5]), ([1, 4], [3, 4]), ([6, 4], [4, 4])],
+        );
+        assert_eq!(first.zobrist_key, second.zobrist_key);
+        assert_eq!(first.zobrist_key, first.compute_zobrist_key());
+
+        // Same placement with the other side to play
+        let black_to_play = Board::from_fen(&first.to_fen().replace(" b ", " w ")).unwrap();
+        assert_ne!(black_to_play.zobrist_key, first.zobrist_key);
+    }
+
+    #[test]
+    fn castling_and_en_passant() {
+        let board = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w KQkq - 0 1").unwrap();
+        let without_rights = Board::from_fen("r3k2r/8/8/8/8/8/8/R3K2R w Kkq - 0 1").unwrap();
+        assert_ne!(board.zobrist_key, without_rights.zobrist_key);
+
+        // The e3 target counts only when a black pawn can take on it
+        let board =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq e3 0 1").unwrap();
+        let without_target =
+            Board::from_fen("rnbqkbnr/pppppppp/8/8/4P3/8/PPPP1PPP/RNBQKBNR b KQkq - 0 1").unwrap();
+        assert_eq!(board.zobrist_key, without_target.zobrist_key);
+
+        let board = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - d6 0 1").unwrap();
+        let without_target = Board::from_fen("4k3/8/8/3pP3/8/8/8/4K3 w - - 0 1").unwrap();
+        assert_ne!(board.zobrist_key, without_target.zobrist_key);
+
+        // Playing and taking back a move gives the key back
+        let mut board =
+            Board::from_fen("r3k2r/p1ppqpb1/bn2pnp1/3PN3/1p2P3/2N2Q1p/PPPBBPPP/R3K2R w KQkq - 0 1")
+                .unwrap();
+        let key = board.zobrist_key;
+        for piece_move in board.legal_moves() {
+            let undo = board.make_move(&piece_move);
+            assert_ne!(board.zobrist_key, key);
+            board.unmake_move(&piece_move, undo);
+            assert_eq!(board.zobrist_key, key);
+        }
+    }
+}